@@ -1,5 +1,12 @@
-use crate::config::{Config, ShortcutCommand};
-use clap::{App, Arg, ArgGroup, ArgMatches, SubCommand};
+use crate::config::{Config, ShortcutCommand, MAX_SIZE_PRECISION};
+use crate::formatter::columns::{parse_columns, Column};
+use crate::formatter::fields::{parse_fields, OutputField};
+use crate::formatter::template::Template;
+use crate::formatter::PluginFormatter;
+use crate::sorter::{is_sort_spec, SORT_KEYS};
+use crate::utils::size::SizeFormat;
+use crate::utils::time::{TimeField, TimeStyle, TIME_STYLES};
+use clap::{App, Arg, ArgGroup, ArgMatches, SubCommand, ValueSource};
 use clap_complete::Shell;
 use std::path::PathBuf;
 
@@ -15,10 +22,12 @@ pub struct Args {
     pub timeline_format: bool,
     pub git_format: bool,
     pub fuzzy_format: bool,
+    pub du_format: bool,
     pub recursive_format: bool,
     pub show_icons: bool,
     pub no_color: bool,
     pub sort_by: String,
+    pub sort_from_config: bool,
     pub sort_reverse: bool,
     pub sort_dirs_first: bool,
     pub sort_case_sensitive: bool,
@@ -29,6 +38,7 @@ pub struct Args {
     pub disable_plugin: Vec<String>,
     pub plugins_dir: PathBuf,
     pub include_dirs: bool,
+    pub one_file_system: bool,
     pub dirs_only: bool,
     pub files_only: bool,
     pub symlinks_only: bool,
@@ -38,10 +48,21 @@ pub struct Args {
     pub no_dotfiles: bool,
     pub almost_all: bool,
     pub dotfiles_only: bool,
+    pub respect_gitignore: bool,
     pub permission_format: String,
     pub hide_group: bool,
     pub relative_dates: bool,
+    pub git_status: bool,
+    pub columns: Option<Vec<Column>>,
+    pub size_format: SizeFormat,
+    pub size_precision: usize,
+    pub disk_usage: bool,
+    pub time_style: TimeStyle,
+    pub time_field: TimeField,
     pub output_mode: OutputMode,
+    pub fields: Option<Vec<OutputField>>,
+    pub template: Option<Template>,
+    pub plugin_formatter: Option<PluginFormatter>,
     pub command: Option<Command>,
 }
 
@@ -54,6 +75,8 @@ pub enum Command {
     PluginAction(String, String, Vec<String>),
     Update(Option<String>),
     Clean,
+    Schema(Option<Vec<OutputField>>),
+    PluginHost(String),
     Shortcut(ShortcutAction),
     GenerateCompletion(Shell, Option<String>, Option<String>),
     Theme,
@@ -85,6 +108,9 @@ pub enum OutputMode {
     Json { pretty: bool },
     Ndjson,
     Csv,
+    Markdown,
+    Html,
+    Template { print0: bool },
 }
 
 impl Args {
@@ -114,14 +140,47 @@ impl Args {
                     .long("csv")
                     .help("Output CSV with header row"),
             )
+            .arg(
+                Arg::with_name("markdown")
+                    .long("markdown")
+                    .help("Output a GitHub-flavored Markdown table"),
+            )
+            .arg(
+                Arg::with_name("html")
+                    .long("html")
+                    .help("Output a self-contained HTML page with sortable columns"),
+            )
             .arg(
                 Arg::with_name("pretty")
                     .long("pretty")
                     .help("Pretty print JSON (only applies to --json)"),
             )
+            .arg(
+                Arg::with_name("fields")
+                    .long("fields")
+                    .takes_value(true)
+                    .help("Comma-separated fields for --json, --ndjson and --csv, in output order, e.g. path,size_bytes,modified,plugin.tags. Rename a field with name=field, e.g. bytes=size_bytes")
+                    .validator(|spec| parse_fields(spec).map(|_| ())),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .long("format")
+                    .takes_value(true)
+                    .help("Print each entry with a template instead of a view, e.g. '{name}\\t{size}\\t{plugin.git_status}'. Placeholders: the --json fields, size, size_human, perms and plugin.<name>. A plugin:formatter value uses a view provided by a plugin instead")
+                    .validator(|value| match PluginFormatter::parse(value) {
+                        Some(_) => Ok(()),
+                        None => Template::parse(value).map(|_| ()),
+                    }),
+            )
+            .arg(
+                Arg::with_name("print0")
+                    .long("print0")
+                    .help("End each entry with a NUL byte instead of a newline, for xargs -0; prints bare paths unless --format is given")
+                    .conflicts_with_all(&["json", "ndjson", "csv", "markdown", "html"]),
+            )
             .group(
                 ArgGroup::new("machine_output")
-                    .args(&["json", "ndjson", "csv"]) // mutually exclusive
+                    .args(&["json", "ndjson", "csv", "markdown", "html", "format"]) // mutually exclusive
                     .multiple(false),
             )
             .arg(
@@ -183,6 +242,11 @@ impl Args {
                     .long("fuzzy")
                     .help("Use interactive fuzzy finder"),
             )
+            .arg(
+                Arg::with_name("du")
+                    .long("du")
+                    .help("Explore disk usage interactively, printing marked entries on exit"),
+            )
             .arg(
                 Arg::with_name("icons")
                     .long("icons")
@@ -202,9 +266,18 @@ impl Args {
                 Arg::with_name("sort")
                     .short('s')
                     .long("sort")
-                    .help("Sort files by name, size, or date")
+                    .help("Sort files by one or more comma-separated keys, each optionally suffixed with :asc or :desc, e.g. ext,size:desc,name. Keys: name, version, size, date, accessed, created, extension, kind, owner, group, permissions, inode, field:<name> and sorters provided by plugins, e.g. complexity")
                     .takes_value(true)
-                    .possible_values(["name", "size", "date"])
+                    .validator(|spec| {
+                        if is_sort_spec(spec) {
+                            Ok(())
+                        } else {
+                            Err(format!(
+                                "expected comma-separated keys from {}, field:<name> or plugin sorters, each optionally followed by :asc or :desc",
+                                SORT_KEYS.join(", ")
+                            ))
+                        }
+                    })
                     .default_value(&config.default_sort),
             )
             .arg(
@@ -272,6 +345,12 @@ impl Args {
                     .long("include-dirs")
                     .help("Include directory sizes in the metadata"),
             )
+            .arg(
+                Arg::with_name("one-file-system")
+                    .short('x')
+                    .long("one-file-system")
+                    .help("Skip directories on other filesystems when totalling directory sizes"),
+            )
             .arg(
                 Arg::with_name("dirs-only")
                     .long("dirs-only")
@@ -324,6 +403,17 @@ impl Args {
                     .long("dotfiles-only")
                     .help("Show only dot files and directories (those starting with a dot)"),
             )
+            .arg(
+                Arg::with_name("gitignore")
+                    .long("gitignore")
+                    .help("Hide entries ignored by .gitignore, .ignore, global git excludes and .git/info/exclude (overrides respect_gitignore config)"),
+            )
+            .arg(
+                Arg::with_name("no-ignore")
+                    .long("no-ignore")
+                    .conflicts_with("gitignore")
+                    .help("Show entries ignored by git (overrides respect_gitignore config)"),
+            )
             .arg(
                 Arg::with_name("permission-format")
                     .long("permission-format")
@@ -332,6 +422,58 @@ impl Args {
                     .possible_values(&["symbolic", "octal", "binary",  "verbose", "compact"])
                     .default_value(&config.permission_format),
             )
+            .arg(
+                Arg::with_name("size-format")
+                    .long("size-format")
+                    .help("Format for displaying sizes (binary, si, bytes, blocks)")
+                    .takes_value(true)
+                    .possible_values(SizeFormat::NAMES)
+                    .default_value(&config.size.format),
+            )
+            .arg(
+                Arg::with_name("size-precision")
+                    .long("size-precision")
+                    .help("Number of decimal places for binary and si sizes")
+                    .takes_value(true)
+                    .validator(|v| match v.parse::<usize>() {
+                        Ok(n) if n <= MAX_SIZE_PRECISION => Ok(()),
+                        _ => Err(format!("expected a number from 0 to {}", MAX_SIZE_PRECISION)),
+                    }),
+            )
+            .arg(
+                Arg::with_name("disk-usage")
+                    .long("disk-usage")
+                    .help("Show the space allocated on disk instead of the apparent size (overrides size.disk_usage config)")
+                    .conflicts_with("apparent-size"),
+            )
+            .arg(
+                Arg::with_name("apparent-size")
+                    .long("apparent-size")
+                    .help("Show the apparent size of files (overrides size.disk_usage config)"),
+            )
+            .arg(
+                Arg::with_name("time-style")
+                    .long("time-style")
+                    .help("Format for displaying timestamps (default, iso, long-iso, full-iso, relative, or a strftime format like +%Y-%m-%d)")
+                    .takes_value(true)
+                    .validator(|v| {
+                        TimeStyle::parse(v).map(|_| ()).ok_or_else(|| {
+                            format!(
+                                "expected one of {} or a strftime format starting with +",
+                                TIME_STYLES.join(", ")
+                            )
+                        })
+                    })
+                    .default_value(&config.time.style),
+            )
+            .arg(
+                Arg::with_name("time-field")
+                    .long("time-field")
+                    .help("Timestamp shown by long, table, timeline and recursive views")
+                    .takes_value(true)
+                    .possible_values(TimeField::NAMES)
+                    .default_value(&config.time.field),
+            )
             .arg(
                 Arg::with_name("hide-group")
                     .long("hide-group")
@@ -342,6 +484,20 @@ impl Args {
                     .long("relative-dates")
                     .help("Show relative dates (e.g., '2h ago') in long format"),
             )
+            .arg(
+                Arg::with_name("git-status")
+                    .long("git-status")
+                    .help("Add a two-character git status column (index, worktree) to the long, table, tree and grid views; directories show the status of the files below them"),
+            )
+            .arg(
+                Arg::with_name("columns")
+                    .long("columns")
+                    .help("Comma-separated columns for long and table formats, e.g. perms,size,mtime,owner,git,field:tags,name. Columns: perms, size, mtime, atime, btime, ctime, inode, links, owner, group, git, name, field:<name>")
+                    .takes_value(true)
+                    .validator(|spec| {
+                        parse_columns(&spec.split(',').collect::<Vec<_>>()).map(|_| ())
+                    }),
+            )
             .subcommand(
                 SubCommand::with_name("install")
                     .about("Install a plugin")
@@ -413,6 +569,23 @@ impl Args {
             .subcommand(
                 SubCommand::with_name("clean").about("This command will clean up invalid plugins"),
             )
+            .subcommand(
+                SubCommand::with_name("schema")
+                    .about("Print the JSON Schema of --json and --ndjson entries")
+                    .arg(
+                        Arg::with_name("fields")
+                            .long("fields")
+                            .takes_value(true)
+                            .help("Print the schema of entries with these --fields instead")
+                            .validator(|spec| parse_fields(spec).map(|_| ())),
+                    ),
+            )
+            .subcommand(
+                SubCommand::with_name("plugin-host")
+                    .about("Serve a plugin library over stdin and stdout (used by plugins.isolate)")
+                    .hide(true)
+                    .arg(Arg::with_name("library").required(true)),
+            )
             .subcommand(
                 SubCommand::with_name("shortcut")
                     .about("Manage command shortcuts")
@@ -519,10 +692,12 @@ impl Args {
                     timeline_format: config.default_format == "timeline",
                     git_format: config.default_format == "git",
                     fuzzy_format: false,
+                    du_format: false,
                     recursive_format: false,
                     show_icons: config.show_icons,
                     no_color: false,
                     sort_by: config.default_sort.clone(),
+                    sort_from_config: true,
                     sort_reverse: false,
                     sort_dirs_first: config.sort.dirs_first,
                     sort_case_sensitive: config.sort.case_sensitive,
@@ -533,6 +708,7 @@ impl Args {
                     disable_plugin: Vec::new(),
                     plugins_dir: config.plugins_dir.clone(),
                     include_dirs: false,
+                    one_file_system: config.size.one_file_system,
                     dirs_only: false,
                     files_only: false,
                     symlinks_only: false,
@@ -542,10 +718,21 @@ impl Args {
                     no_dotfiles: config.filter.no_dotfiles,
                     almost_all: false,
                     dotfiles_only: false,
+                    respect_gitignore: config.filter.respect_gitignore,
                     permission_format: config.permission_format.clone(),
                     hide_group: config.formatters.long.hide_group,
                     relative_dates: config.formatters.long.relative_dates,
+                    git_status: false,
+                    columns: config_columns(config),
+                    size_format: SizeFormat::parse(&config.size.format).unwrap_or_default(),
+                    size_precision: config.size.precision,
+                    disk_usage: config.size.disk_usage,
+                    time_style: TimeStyle::parse(&config.time.style).unwrap_or_default(),
+                    time_field: TimeField::parse(&config.time.field).unwrap_or_default(),
                     output_mode: OutputMode::Human,
+                    fields: None,
+                    template: None,
+                    plugin_formatter: None,
                     command: Some(Command::Shortcut(ShortcutAction::Run(
                         potential_shortcut.clone(),
                         args[2..].to_vec(),
@@ -608,6 +795,16 @@ impl Args {
             }
         } else if matches.subcommand_matches("clean").is_some() {
             Some(Command::Clean)
+        } else if let Some(schema_matches) = matches.subcommand_matches("schema") {
+            Some(Command::Schema(
+                schema_matches
+                    .value_of("fields")
+                    .and_then(|spec| parse_fields(spec).ok()),
+            ))
+        } else if let Some(host_matches) = matches.subcommand_matches("plugin-host") {
+            Some(Command::PluginHost(
+                host_matches.value_of("library").unwrap().to_string(),
+            ))
         } else if let Some(install_matches) = matches.subcommand_matches("install") {
             if let Some(github_url) = install_matches.value_of("git") {
                 Some(Command::Install(InstallSource::GitHub(
@@ -653,7 +850,9 @@ impl Args {
             })
         };
 
-        let has_format_flag = matches.is_present("long")
+        let plugin_formatter = matches.value_of("format").and_then(PluginFormatter::parse);
+        let has_format_flag = plugin_formatter.is_some()
+            || matches.is_present("long")
             || matches.is_present("tree")
             || matches.is_present("table")
             || matches.is_present("grid")
@@ -661,6 +860,7 @@ impl Args {
             || matches.is_present("timeline")
             || matches.is_present("git")
             || matches.is_present("fuzzy")
+            || matches.is_present("du")
             || matches.is_present("recursive");
 
         Args {
@@ -685,6 +885,7 @@ impl Args {
             git_format: matches.is_present("git")
                 || (!has_format_flag && config.default_format == "git"),
             fuzzy_format: matches.is_present("fuzzy"),
+            du_format: matches.is_present("du"),
             recursive_format: matches.is_present("recursive")
                 || (!has_format_flag && config.default_format == "recursive"),
             show_icons: matches.is_present("icons")
@@ -694,6 +895,7 @@ impl Args {
                 .value_of("sort")
                 .unwrap_or(&config.default_sort)
                 .to_string(),
+            sort_from_config: matches.value_source("sort") != Some(ValueSource::CommandLine),
             sort_reverse: matches.is_present("sort-reverse"),
             sort_dirs_first: matches.is_present("sort-dirs-first") || config.sort.dirs_first,
             sort_case_sensitive: matches.is_present("sort-case-sensitive")
@@ -714,6 +916,7 @@ impl Args {
                 .map(PathBuf::from)
                 .unwrap_or_else(|| config.plugins_dir.clone()),
             include_dirs: matches.is_present("include-dirs") || config.include_dirs,
+            one_file_system: matches.is_present("one-file-system") || config.size.one_file_system,
             dirs_only: matches.is_present("dirs-only"),
             files_only: matches.is_present("files-only"),
             symlinks_only: matches.is_present("symlinks-only"),
@@ -726,6 +929,8 @@ impl Args {
                 && config.filter.no_dotfiles,
             almost_all: matches.is_present("almost-all"),
             dotfiles_only: matches.is_present("dotfiles-only"),
+            respect_gitignore: matches.is_present("gitignore")
+                || (!matches.is_present("no-ignore") && config.filter.respect_gitignore),
             permission_format: matches
                 .value_of("permission-format")
                 .unwrap_or(&config.permission_format)
@@ -733,6 +938,29 @@ impl Args {
             hide_group: matches.is_present("hide-group") || config.formatters.long.hide_group,
             relative_dates: matches.is_present("relative-dates")
                 || config.formatters.long.relative_dates,
+            git_status: matches.is_present("git-status"),
+            columns: match matches.value_of("columns") {
+                Some(spec) => parse_columns(&spec.split(',').collect::<Vec<_>>()).ok(),
+                None => config_columns(config),
+            },
+            size_format: matches
+                .value_of("size-format")
+                .and_then(SizeFormat::parse)
+                .unwrap_or_default(),
+            size_precision: matches
+                .value_of("size-precision")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(config.size.precision),
+            disk_usage: matches.is_present("disk-usage")
+                || (!matches.is_present("apparent-size") && config.size.disk_usage),
+            time_style: matches
+                .value_of("time-style")
+                .and_then(TimeStyle::parse)
+                .unwrap_or_default(),
+            time_field: matches
+                .value_of("time-field")
+                .and_then(TimeField::parse)
+                .unwrap_or_default(),
             output_mode: {
                 let pretty = matches.is_present("pretty");
                 if matches.is_present("json") {
@@ -741,11 +969,45 @@ impl Args {
                     OutputMode::Ndjson
                 } else if matches.is_present("csv") {
                     OutputMode::Csv
+                } else if matches.is_present("markdown") {
+                    OutputMode::Markdown
+                } else if matches.is_present("html") {
+                    OutputMode::Html
+                } else if matches.is_present("print0")
+                    || (matches.is_present("format") && plugin_formatter.is_none())
+                {
+                    OutputMode::Template {
+                        print0: matches.is_present("print0"),
+                    }
                 } else {
                     OutputMode::Human
                 }
             },
+            fields: matches
+                .value_of("fields")
+                .and_then(|spec| parse_fields(spec).ok()),
+            template: matches
+                .value_of("format")
+                .filter(|_| plugin_formatter.is_none())
+                .and_then(|template| Template::parse(template).ok()),
+            plugin_formatter,
             command,
         }
     }
 }
+
+fn config_columns(config: &Config) -> Option<Vec<Column>> {
+    let columns = &config.formatters.long.columns;
+    if columns.is_empty() {
+        None
+    } else {
+        parse_columns(columns)
+            .map_err(|e| {
+                eprintln!(
+                    "Warning: formatters.long.columns: {}; using the default columns",
+                    e
+                )
+            })
+            .ok()
+    }
+}
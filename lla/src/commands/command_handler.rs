@@ -164,7 +164,15 @@ pub fn handle_command(
         Some(Command::PluginAction(plugin_name, action, action_args)) => {
             plugin_manager.perform_plugin_action(plugin_name, action, action_args)
         }
-        Some(Command::Clean) => unreachable!(),
+        Some(Command::Schema(fields)) => {
+            let schema = match fields.as_ref().or(args.fields.as_ref()) {
+                Some(fields) => crate::formatter::schema::fields_schema(fields),
+                None => crate::formatter::schema::entry_schema(),
+            };
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+            Ok(())
+        }
+        Some(Command::Clean) | Some(Command::PluginHost(_)) => unreachable!(),
         None => list_directory(args, plugin_manager, config_error),
     }
 }
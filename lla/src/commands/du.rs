@@ -0,0 +1,441 @@
+use crate::commands::args::Args;
+use crate::error::{LlaError, Result};
+use crate::formatter::columns::visible_width;
+use crate::formatter::SizeMapFormatter;
+use crate::utils::color::colorize_file_name;
+use crate::utils::dir_size::DirSizes;
+use crate::utils::icons::format_with_icon;
+use crate::utils::size::{entry_size, format_size};
+use colored::*;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    execute, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+use std::collections::BTreeSet;
+use std::fs::{self, Metadata};
+use std::io::{self, stderr, Write};
+use std::path::{Path, PathBuf};
+use unicode_width::UnicodeWidthChar;
+
+const MAX_BAR_WIDTH: usize = 36;
+
+struct Node {
+    path: PathBuf,
+    name: String,
+    size: u64,
+    is_dir: bool,
+}
+
+/// One directory on the way from the root to the one being shown.
+struct Level {
+    path: PathBuf,
+    metadata: Metadata,
+    size: u64,
+    nodes: Vec<Node>,
+    selected: usize,
+    offset: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SortMode {
+    Size,
+    Name,
+}
+
+enum Action {
+    Continue,
+    Quit,
+}
+
+/// Interactive disk usage explorer in the spirit of ncdu. Directory totals
+/// are computed once up front; browsing only reads the directory on screen.
+struct Explorer {
+    sizes: DirSizes,
+    levels: Vec<Level>,
+    sort: SortMode,
+    marked: BTreeSet<PathBuf>,
+    pending_delete: Option<PathBuf>,
+    status: Option<String>,
+    show_icons: bool,
+}
+
+/// Runs the `--du` explorer on `args.directory`. Entries marked with space are
+/// printed to stdout on exit, one per line; the interface itself is drawn on
+/// stderr so the output can be piped.
+pub fn explore(args: &Args) -> Result<()> {
+    let root = PathBuf::from(&args.directory);
+    let metadata = fs::metadata(&root)?;
+    if !metadata.is_dir() {
+        return Err(LlaError::Other(format!(
+            "--du needs a directory, got '{}'",
+            args.directory
+        )));
+    }
+
+    eprintln!("Calculating directory sizes...");
+    let sizes = DirSizes::compute(&root, args.one_file_system);
+    let mut explorer = Explorer {
+        sizes,
+        levels: Vec::new(),
+        sort: SortMode::Size,
+        marked: BTreeSet::new(),
+        pending_delete: None,
+        status: None,
+        show_icons: args.show_icons,
+    };
+    explorer.push_level(root, metadata);
+
+    terminal::enable_raw_mode()?;
+    execute!(stderr(), terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = explorer.run();
+    execute!(stderr(), terminal::LeaveAlternateScreen, cursor::Show)?;
+    terminal::disable_raw_mode()?;
+    result?;
+
+    for path in &explorer.marked {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+impl Explorer {
+    fn run(&mut self) -> Result<()> {
+        loop {
+            self.render()?;
+            if let Event::Key(key) = event::read()? {
+                if let Action::Quit = self.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn current(&mut self) -> &mut Level {
+        self.levels.last_mut().expect("explorer has a root level")
+    }
+
+    fn selected(&self) -> Option<&Node> {
+        let level = self.levels.last()?;
+        level.nodes.get(level.selected)
+    }
+
+    fn push_level(&mut self, path: PathBuf, metadata: Metadata) {
+        let mut nodes = Vec::new();
+        if let Ok(entries) = fs::read_dir(&path) {
+            for entry in entries.flatten() {
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                let is_dir = metadata.is_dir();
+                let size = if is_dir {
+                    self.sizes.get(&metadata).map_or(0, |size| size.shown())
+                } else if metadata.is_symlink() {
+                    0
+                } else {
+                    entry_size(&metadata)
+                };
+                nodes.push(Node {
+                    path: entry.path(),
+                    name: entry.file_name().to_string_lossy().into_owned(),
+                    size,
+                    is_dir,
+                });
+            }
+        }
+
+        let size = self
+            .sizes
+            .get(&metadata)
+            .map(|size| size.shown())
+            .unwrap_or_else(|| nodes.iter().map(|node| node.size).sum());
+        let mut level = Level {
+            path,
+            metadata,
+            size,
+            nodes,
+            selected: 0,
+            offset: 0,
+        };
+        sort_nodes(&mut level.nodes, self.sort);
+        self.levels.push(level);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if let Some(path) = self.pending_delete.take() {
+            if key.code == KeyCode::Char('y') {
+                self.delete(&path);
+            } else {
+                self.status = Some("Delete cancelled".to_string());
+            }
+            return Action::Continue;
+        }
+        self.status = None;
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('c'), KeyModifiers::CONTROL)
+            | (KeyCode::Char('q'), _)
+            | (KeyCode::Esc, _) => return Action::Quit,
+            (KeyCode::Up, _) | (KeyCode::Char('k'), _) => self.move_selection(-1),
+            (KeyCode::Down, _) | (KeyCode::Char('j'), _) => self.move_selection(1),
+            (KeyCode::PageUp, _) => self.move_selection(-(page_size() as isize)),
+            (KeyCode::PageDown, _) => self.move_selection(page_size() as isize),
+            (KeyCode::Home, _) | (KeyCode::Char('g'), _) => self.move_selection(isize::MIN / 2),
+            (KeyCode::End, _) | (KeyCode::Char('G'), _) => self.move_selection(isize::MAX / 2),
+            (KeyCode::Enter, _) | (KeyCode::Right, _) | (KeyCode::Char('l'), _) => self.open(),
+            (KeyCode::Left, _) | (KeyCode::Backspace, _) | (KeyCode::Char('h'), _)
+                if self.levels.len() > 1 =>
+            {
+                self.levels.pop();
+            }
+            (KeyCode::Char('s'), _) => {
+                self.sort = match self.sort {
+                    SortMode::Size => SortMode::Name,
+                    SortMode::Name => SortMode::Size,
+                };
+                for level in &mut self.levels {
+                    let selected = level.nodes.get(level.selected).map(|n| n.path.clone());
+                    sort_nodes(&mut level.nodes, self.sort);
+                    level.selected = selected
+                        .and_then(|path| level.nodes.iter().position(|n| n.path == path))
+                        .unwrap_or(0);
+                }
+            }
+            (KeyCode::Char(' '), _) => {
+                if let Some(path) = self.selected().map(|node| node.path.clone()) {
+                    if !self.marked.remove(&path) {
+                        self.marked.insert(path);
+                    }
+                    self.move_selection(1);
+                }
+            }
+            (KeyCode::Char('d'), _) => {
+                if let Some(path) = self.selected().map(|node| node.path.clone()) {
+                    self.status = Some(format!("Delete {}? (y/N)", path.display()));
+                    self.pending_delete = Some(path);
+                }
+            }
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let level = self.current();
+        let last = level.nodes.len().saturating_sub(1) as isize;
+        level.selected = (level.selected as isize + delta).clamp(0, last) as usize;
+    }
+
+    fn open(&mut self) {
+        let Some(path) = self
+            .selected()
+            .filter(|node| node.is_dir)
+            .map(|node| node.path.clone())
+        else {
+            return;
+        };
+        match fs::symlink_metadata(&path) {
+            Ok(metadata) => self.push_level(path, metadata),
+            Err(err) => self.status = Some(format!("{}: {}", path.display(), err)),
+        }
+    }
+
+    fn delete(&mut self, path: &Path) {
+        let Some(is_dir) = self.selected().map(|node| node.is_dir) else {
+            return;
+        };
+        let result = if is_dir {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        };
+        if let Err(err) = result {
+            self.status = Some(format!("Failed to delete {}: {}", path.display(), err));
+            return;
+        }
+
+        let level = self.current();
+        let index = level.selected;
+        let freed = level.nodes.remove(index).size;
+        level.selected = level.selected.min(level.nodes.len().saturating_sub(1));
+
+        // Every directory above the deleted entry shrinks by the same amount,
+        // both on screen and in the totals used when re-entering it later.
+        let depth = self.levels.len();
+        for (i, level) in self.levels.iter_mut().enumerate() {
+            level.size = level.size.saturating_sub(freed);
+            self.sizes.shrink(&level.metadata, freed);
+            if i + 1 < depth {
+                if let Some(node) = level.nodes.get_mut(level.selected) {
+                    node.size = node.size.saturating_sub(freed);
+                }
+            }
+        }
+        self.marked.retain(|marked| !marked.starts_with(path));
+        self.status = Some(format!(
+            "Deleted {} ({} freed)",
+            path.display(),
+            format_size(freed)
+        ));
+    }
+
+    fn render(&mut self) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let width = width as usize;
+        let visible = page_size();
+
+        let show_icons = self.show_icons;
+        let sort = self.sort;
+        let marked_count = self.marked.len();
+        let status = self.status.clone();
+
+        let level = self.levels.last_mut().expect("explorer has a root level");
+        if level.selected < level.offset {
+            level.offset = level.selected;
+        } else if level.selected >= level.offset + visible {
+            level.offset = level.selected + 1 - visible;
+        }
+
+        let mut out = stderr();
+        queue!(out, terminal::Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+
+        let header = format!(
+            " {}  {}  {}",
+            level.path.display().to_string().bold(),
+            format_size(level.size).yellow(),
+            format!("({} entries)", level.nodes.len()).bright_black()
+        );
+        queue!(out, Print(truncate(&header, width)))?;
+        queue!(
+            out,
+            cursor::MoveTo(0, 1),
+            Print("─".repeat(width).bright_black())
+        )?;
+
+        let size_width = level
+            .nodes
+            .iter()
+            .map(|node| format_size(node.size).len())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        let bar_width = (width / 3).min(MAX_BAR_WIDTH);
+
+        for (row, (index, node)) in level
+            .nodes
+            .iter()
+            .enumerate()
+            .skip(level.offset)
+            .take(visible)
+            .enumerate()
+        {
+            let selected = index == level.selected;
+            let percentage = if level.size == 0 {
+                0.0
+            } else {
+                node.size as f64 / level.size as f64 * 100.0
+            };
+            let bar = SizeMapFormatter::create_bar(percentage, bar_width, node.is_dir);
+            let name = if selected {
+                colorize_file_name(&node.path).underline().to_string()
+            } else {
+                colorize_file_name(&node.path).to_string()
+            };
+
+            let line = format!(
+                "{}{} {:>size_width$} {} {}",
+                if selected { "→" } else { " " },
+                if self.marked.contains(&node.path) {
+                    "*".yellow().to_string()
+                } else {
+                    " ".to_string()
+                },
+                format_size(node.size),
+                bar,
+                format_with_icon(&node.path, name, show_icons),
+                size_width = size_width
+            );
+            queue!(
+                out,
+                cursor::MoveTo(0, (row + 2) as u16),
+                Print(truncate(&line, width))
+            )?;
+        }
+
+        if level.nodes.is_empty() {
+            queue!(
+                out,
+                cursor::MoveTo(0, 2),
+                Print("  (empty directory)".bright_black())
+            )?;
+        }
+
+        let footer = status.unwrap_or_else(|| {
+            format!(
+                "↑↓ move  →/enter open  ← back  s sort: {}  space mark ({})  d delete  q quit",
+                if sort == SortMode::Size {
+                    "size"
+                } else {
+                    "name"
+                },
+                marked_count
+            )
+        });
+        queue!(
+            out,
+            cursor::MoveTo(0, height.saturating_sub(1)),
+            Print(truncate(&format!(" {}", footer), width).bright_black())
+        )?;
+
+        out.flush()
+    }
+}
+
+fn sort_nodes(nodes: &mut [Node], sort: SortMode) {
+    match sort {
+        SortMode::Size => nodes.sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name))),
+        SortMode::Name => nodes.sort_by(|a, b| a.name.cmp(&b.name)),
+    }
+}
+
+/// Rows available for entries, leaving room for the header and footer.
+fn page_size() -> usize {
+    terminal::size()
+        .map(|(_, height)| height as usize)
+        .unwrap_or(24)
+        .saturating_sub(3)
+        .max(1)
+}
+
+/// Cuts `line` to `width` visible columns, keeping ANSI escapes intact.
+fn truncate(line: &str, width: usize) -> String {
+    if visible_width(line) <= width {
+        return line.to_string();
+    }
+
+    let mut result = String::new();
+    let mut current = 0;
+    let mut in_escape = false;
+    for c in line.chars() {
+        if c == '\x1B' {
+            in_escape = true;
+        }
+        if in_escape {
+            result.push(c);
+            if c == 'm' {
+                in_escape = false;
+            }
+            continue;
+        }
+        let w = c.width().unwrap_or(0);
+        if current + w > width {
+            break;
+        }
+        current += w;
+        result.push(c);
+    }
+    result.push_str("\x1B[0m");
+    result
+}
@@ -1,11 +1,16 @@
 use crate::commands::args::{Args, OutputMode};
+use crate::commands::du;
 use crate::config::Config;
-use crate::error::Result;
+use crate::error::{LlaError, Result};
 use crate::filter::{
-    CaseInsensitiveFilter, CompositeFilter, ExtensionFilter, FileFilter, FilterOperation,
-    GlobFilter, PatternFilter, RegexFilter,
+    CaseInsensitiveFilter, ExtensionFilter, FieldFilter, FileFilter, FilterExpr, GlobFilter,
+    MetadataFilter, PatternFilter, PluginFilter, RegexFilter,
+};
+use crate::formatter::columns::{self, Column};
+use crate::formatter::{
+    csv as csv_writer, html as html_writer, json as json_writer, markdown as markdown_writer,
+    template::{self as template_writer, Template},
 };
-use crate::formatter::{csv as csv_writer, json as json_writer};
 use crate::formatter::{
     DefaultFormatter, FileFormatter, FuzzyFormatter, GitFormatter, GridFormatter, LongFormatter,
     RecursiveFormatter, SizeMapFormatter, TableFormatter, TimelineFormatter, TreeFormatter,
@@ -14,14 +19,20 @@ use crate::lister::{
     archive as archive_lister, BasicLister, FileLister, FuzzyLister, RecursiveLister,
 };
 use crate::plugin::PluginManager;
-use crate::sorter::{AlphabeticalSorter, DateSorter, FileSorter, SizeSorter, SortOptions};
+use crate::sorter::{
+    canonical_key, parse_sort_spec, AlphabeticalSorter, DateSorter, ExtensionSorter, FieldSorter,
+    FileSorter, InodeSorter, KindSorter, MultiSorter, OwnerKey, OwnerSorter, PermissionsSorter,
+    PluginSorter, SizeSorter, SortDirection, SortOptions, TimeKey, SORT_KEYS,
+};
+use crate::utils::dir_size::{DirSize, DirSizes};
+use crate::utils::metadata::convert_metadata;
+use crate::utils::size::ALLOCATED_SIZE_FIELD;
 use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata};
 use rayon::prelude::*;
 use std::collections::HashMap;
-use std::os::unix::fs::MetadataExt;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::UNIX_EPOCH;
+use std::thread::{self, JoinHandle};
 
 pub fn list_directory(
     args: &Args,
@@ -43,155 +54,150 @@ pub fn list_directory(
         }
     }
 
+    if args.du_format {
+        return du::explore(args);
+    }
+    if args.plugin_formatter.is_some() && !matches!(args.output_mode, OutputMode::Human) {
+        return Err(LlaError::Other(
+            "--print0 can't be combined with a plugin formatter".to_string(),
+        ));
+    }
+
     let lister = create_lister(args);
-    let sorter = create_sorter(args);
-    let filter = create_filter(args);
+    let sorter = sorter_for_args(args, plugin_manager)?;
+    let filter = create_filter(args, plugin_manager)?;
     let formatter = create_formatter(args);
     let format = get_format(args);
+    // Sorting on plugin fields or showing them as columns needs decorations
+    // even in views that don't show plugin output.
+    let needs_fields = sorter.requires_decoration()
+        || args
+            .columns
+            .as_deref()
+            .is_some_and(columns::requires_decoration)
+        || args
+            .template
+            .as_ref()
+            .is_some_and(Template::uses_plugin_fields);
+    let format = if needs_fields && !PluginManager::decorates_format(format) {
+        "default"
+    } else {
+        format
+    };
 
     // Archive auto-detection branch
     let p = std::path::Path::new(&args.directory);
     let path_is_archive = p.is_file() && archive_lister::is_archive_path_str(&args.directory);
     if path_is_archive {
-        let mut decorated_files =
+        let decorated_files =
             list_and_decorate_archive_entries(args, &filter, plugin_manager, format)?;
         let decorated_files = if !args.tree_format && !args.recursive_format {
-            sort_files(decorated_files, &sorter, args)?
+            sort_files(decorated_files, &sorter, args, plugin_manager)?
         } else {
             decorated_files
         };
-
-        return match args.output_mode {
-            OutputMode::Human => {
-                let formatted_output = formatter.format_files(
-                    decorated_files.as_slice(),
-                    plugin_manager,
-                    args.depth,
-                )?;
-                println!("{}", formatted_output);
-                Ok(())
-            }
-            OutputMode::Json { pretty } => {
-                let include_git_status = args.git_format;
-                json_writer::write_json_array_stream(
-                    decorated_files.into_iter(),
-                    plugin_manager,
-                    pretty,
-                    include_git_status,
-                )
-            }
-            OutputMode::Ndjson => {
-                let include_git_status = args.git_format;
-                json_writer::write_ndjson_stream(
-                    decorated_files.into_iter(),
-                    plugin_manager,
-                    include_git_status,
-                )
-            }
-            OutputMode::Csv => {
-                let include_git_status = args.git_format;
-                csv_writer::write_csv_stream(
-                    decorated_files.into_iter(),
-                    plugin_manager,
-                    include_git_status,
-                )
-            }
-        };
+        return write_entries(args, decorated_files, formatter.as_ref(), plugin_manager);
     }
 
     // Single file path handling: allow listing one file
     if p.is_file() {
         let decorated_files = list_and_decorate_single_file(args, &filter, plugin_manager, format)?;
         let decorated_files = if !args.tree_format && !args.recursive_format {
-            sort_files(decorated_files, &sorter, args)?
+            sort_files(decorated_files, &sorter, args, plugin_manager)?
         } else {
             decorated_files
         };
+        return write_entries(args, decorated_files, formatter.as_ref(), plugin_manager);
+    }
 
-        return match args.output_mode {
-            OutputMode::Human => {
-                let formatted_output = formatter.format_files(
-                    decorated_files.as_slice(),
-                    plugin_manager,
-                    args.depth,
-                )?;
-                println!("{}", formatted_output);
-                Ok(())
-            }
-            OutputMode::Json { pretty } => {
-                let include_git_status = args.git_format;
-                json_writer::write_json_array_stream(
-                    decorated_files.into_iter(),
-                    plugin_manager,
-                    pretty,
-                    include_git_status,
-                )
-            }
-            OutputMode::Ndjson => {
-                let include_git_status = args.git_format;
-                json_writer::write_ndjson_stream(
-                    decorated_files.into_iter(),
-                    plugin_manager,
-                    include_git_status,
-                )
-            }
-            OutputMode::Csv => {
-                let include_git_status = args.git_format;
-                csv_writer::write_csv_stream(
-                    decorated_files.into_iter(),
-                    plugin_manager,
-                    include_git_status,
-                )
-            }
-        };
+    // The views need the whole listing to sort or group it, so only machine
+    // and report output of tree and recursive listings is streamed.
+    let streaming = args.plugin_formatter.is_none()
+        && (args.recursive_format || args.tree_format)
+        && !matches!(args.output_mode, OutputMode::Human);
+    if streaming {
+        return stream_directory(args, &lister, &filter, plugin_manager, format);
     }
 
     let decorated_files = list_and_decorate_files(args, &lister, &filter, plugin_manager, format)?;
 
     let decorated_files = if !args.tree_format && !args.recursive_format {
-        sort_files(decorated_files, &sorter, args)?
+        sort_files(decorated_files, &sorter, args, plugin_manager)?
     } else {
         decorated_files
     };
 
+    write_entries(args, decorated_files, formatter.as_ref(), plugin_manager)
+}
+
+/// Prints a listing with `formatter`, or in the selected machine or report
+/// output mode.
+fn write_entries(
+    args: &Args,
+    entries: Vec<DecoratedEntry>,
+    formatter: &dyn FileFormatter,
+    plugin_manager: &mut PluginManager,
+) -> Result<()> {
     match args.output_mode {
         OutputMode::Human => {
             let formatted_output =
-                formatter.format_files(decorated_files.as_slice(), plugin_manager, args.depth)?;
+                formatter.format_files(entries.as_slice(), plugin_manager, args.depth)?;
             println!("{}", formatted_output);
             Ok(())
         }
+        _ => write_output(args, entries),
+    }
+}
+
+/// Writes entries in a machine or report output mode as they arrive. Human
+/// output goes through the views instead.
+fn write_output<I>(args: &Args, entries: I) -> Result<()>
+where
+    I: IntoIterator<Item = DecoratedEntry>,
+{
+    // Only include git status if git format was requested
+    let include_git_status = args.git_format;
+    let fields = args.fields.as_deref();
+    match args.output_mode {
+        OutputMode::Human => Err(LlaError::Other(
+            "human output is written by a view".to_string(),
+        )),
         OutputMode::Json { pretty } => {
-            // Only include git status if git format was requested
-            let include_git_status = args.git_format;
-            json_writer::write_json_array_stream(
-                decorated_files.into_iter(),
-                plugin_manager,
-                pretty,
-                include_git_status,
-            )
-        }
-        OutputMode::Ndjson => {
-            let include_git_status = args.git_format;
-            json_writer::write_ndjson_stream(
-                decorated_files.into_iter(),
-                plugin_manager,
-                include_git_status,
-            )
-        }
-        OutputMode::Csv => {
-            let include_git_status = args.git_format;
-            csv_writer::write_csv_stream(
-                decorated_files.into_iter(),
-                plugin_manager,
-                include_git_status,
-            )
+            json_writer::write_json_array_stream(entries, pretty, include_git_status, fields)
         }
+        OutputMode::Ndjson => json_writer::write_ndjson_stream(entries, include_git_status, fields),
+        OutputMode::Csv => csv_writer::write_csv_stream(entries, include_git_status, fields),
+        OutputMode::Markdown => markdown_writer::write_markdown(entries, include_git_status),
+        OutputMode::Html => html_writer::write_html(entries, include_git_status, &args.directory),
+        OutputMode::Template { print0 } => write_template(args, entries, print0),
     }
 }
 
+fn stream_directory(
+    args: &Args,
+    lister: &Arc<dyn FileLister + Send + Sync>,
+    filter: &Arc<dyn FileFilter + Send + Sync>,
+    plugin_manager: &mut PluginManager,
+    format: &str,
+) -> Result<()> {
+    let entries = EntryStream::new(args, lister, filter, plugin_manager, format)?;
+    write_output(args, entries)
+}
+
+fn write_template<I>(args: &Args, entries: I, print0: bool) -> Result<()>
+where
+    I: IntoIterator<Item = DecoratedEntry>,
+{
+    let path_only = Template::path_only();
+    let template = args.template.as_ref().unwrap_or(&path_only);
+    template_writer::write_template(entries, template, print0, args.git_format)
+}
+
 pub fn get_format(args: &Args) -> &'static str {
-    if args.fuzzy_format {
+    // Plugin formatters get the entries decorated as for the long view.
+    if args.plugin_formatter.is_some() {
+        "long"
+    } else if args.fuzzy_format {
         "fuzzy"
     } else if args.long_format {
         "long"
@@ -208,60 +214,10 @@ pub fn get_format(args: &Args) -> &'static str {
     }
 }
 
-pub fn convert_metadata(metadata: &std::fs::Metadata) -> EntryMetadata {
-    EntryMetadata {
-        size: metadata.len(),
-        modified: metadata
-            .modified()
-            .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
-            .unwrap_or(0),
-        accessed: metadata
-            .accessed()
-            .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
-            .unwrap_or(0),
-        created: metadata
-            .created()
-            .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
-            .unwrap_or(0),
-        is_dir: metadata.is_dir(),
-        is_file: metadata.is_file(),
-        is_symlink: metadata.is_symlink(),
-        permissions: metadata.mode(),
-        uid: metadata.uid(),
-        gid: metadata.gid(),
-    }
-}
-
-fn calculate_dir_size(path: &std::path::Path) -> std::io::Result<u64> {
-    use rayon::prelude::*;
-
-    if !path.is_dir() {
-        return Ok(0);
-    }
-
-    let entries: Vec<_> = std::fs::read_dir(path)?.collect::<std::io::Result<_>>()?;
-
-    entries
-        .par_iter()
-        .try_fold(
-            || 0u64,
-            |acc, entry| {
-                let metadata = entry.metadata()?;
-                if metadata.is_symlink() {
-                    return Ok(acc);
-                }
-
-                let path = entry.path();
-                let size = if metadata.is_dir() {
-                    calculate_dir_size(&path)?
-                } else {
-                    metadata.len()
-                };
-
-                Ok(acc + size)
-            },
-        )
-        .try_reduce(|| 0, |a, b| Ok(a + b))
+/// Directory sizes are totals of their whole subtree with `--include-dirs`,
+/// and always in `--tree --long`, which has a size column.
+fn wants_dir_sizes(args: &Args) -> bool {
+    args.include_dirs || (args.tree_format && args.long_format)
 }
 
 pub fn list_and_decorate_files(
@@ -271,128 +227,224 @@ pub fn list_and_decorate_files(
     plugin_manager: &mut PluginManager,
     format: &str,
 ) -> Result<Vec<DecoratedEntry>> {
-    let mut entries: Vec<DecoratedEntry> = lister
-        .list_files(
+    Ok(EntryStream::new(args, lister, filter, plugin_manager, format)?.collect())
+}
+
+/// Lists, filters and decorates entries one lister batch at a time, so output
+/// can start while a recursive walk is still in progress.
+///
+/// Directory sizes are computed on a separate thread while the listing
+/// streams. Directories need their total, so they are held back and come
+/// last, once that walk has finished.
+pub struct EntryStream<'a> {
+    args: &'a Args,
+    filter: &'a Arc<dyn FileFilter + Send + Sync>,
+    plugin_manager: &'a mut PluginManager,
+    decoration_format: &'a str,
+    batches: Box<dyn Iterator<Item = Vec<PathBuf>> + Send>,
+    pending: std::vec::IntoIter<DecoratedEntry>,
+    dir_sizes: Option<DirSizes>,
+    size_walk: Option<JoinHandle<DirSizes>>,
+    held_dirs: Vec<PathBuf>,
+}
+
+impl<'a> EntryStream<'a> {
+    pub fn new(
+        args: &'a Args,
+        lister: &Arc<dyn FileLister + Send + Sync>,
+        filter: &'a Arc<dyn FileFilter + Send + Sync>,
+        plugin_manager: &'a mut PluginManager,
+        format: &'a str,
+    ) -> Result<Self> {
+        let batches = lister.list_batches(
             &args.directory,
             args.tree_format || args.recursive_format,
             args.depth,
-        )?
-        .into_par_iter()
-        .filter_map(|path| {
-            let fs_metadata = match path.symlink_metadata() {
-                Ok(meta) => meta,
-                Err(_) => {
-                    if let Some(file_name) = path.file_name() {
-                        let mut custom_fields = HashMap::new();
-                        custom_fields.insert("invalid_symlink".to_string(), "true".to_string());
-
-                        if let Ok(target) = std::fs::read_link(&path) {
-                            custom_fields.insert(
-                                "symlink_target".to_string(),
-                                target.to_string_lossy().into_owned(),
-                            );
-                        }
+        )?;
+        let size_walk = wants_dir_sizes(args).then(|| {
+            let root = PathBuf::from(&args.directory);
+            let one_file_system = args.one_file_system;
+            thread::spawn(move || DirSizes::compute(&root, one_file_system))
+        });
 
-                        return Some(DecoratedEntry {
-                            path: path.to_string_lossy().into_owned(),
-                            metadata: Some(EntryMetadata {
-                                size: 0,
-                                modified: 0,
-                                accessed: 0,
-                                created: 0,
-                                is_dir: false,
-                                is_file: false,
-                                is_symlink: true,
-                                permissions: 0,
-                                uid: 0,
-                                gid: 0,
-                            }),
-                            custom_fields,
-                        });
-                    }
-                    return None;
-                }
-            };
+        Ok(Self {
+            args,
+            filter,
+            plugin_manager,
+            decoration_format: decoration_format(filter, format),
+            batches,
+            pending: Vec::new().into_iter(),
+            dir_sizes: None,
+            size_walk,
+            held_dirs: Vec::new(),
+        })
+    }
 
-            let mut metadata = convert_metadata(&fs_metadata);
-
-            let is_dotfile = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .map(|n| n.starts_with('.'))
-                .unwrap_or(false);
-
-            let is_current_or_parent_dir = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .map(|n| n == "." || n == "..")
-                .unwrap_or(false);
-
-            if args.dotfiles_only && !is_dotfile {
-                return None;
-            } else if args.no_dotfiles && is_dotfile {
-                return None;
-            } else if args.almost_all && is_current_or_parent_dir {
-                return None;
-            }
+    fn process_batch(&mut self, mut paths: Vec<PathBuf>) -> Vec<DecoratedEntry> {
+        if self.size_walk.is_some() {
+            let (dirs, others): (Vec<PathBuf>, Vec<PathBuf>) = paths
+                .into_par_iter()
+                .partition(|path| path.symlink_metadata().is_ok_and(|m| m.is_dir()));
+            self.held_dirs.extend(dirs);
+            paths = others;
+        }
 
-            let should_include = if args.dirs_only {
-                metadata.is_dir
-            } else if args.files_only {
-                metadata.is_file
-            } else if args.symlinks_only {
-                metadata.is_symlink && !args.no_symlinks
-            } else {
-                let include_dirs = !args.no_dirs;
-                let include_files = !args.no_files;
-                let include_symlinks = !args.no_symlinks;
-
-                (metadata.is_dir && include_dirs)
-                    || (metadata.is_file && include_files)
-                    || (metadata.is_symlink && include_symlinks)
-            };
+        let args = self.args;
+        let filter = self.filter;
+        let dir_sizes = self.dir_sizes.as_ref();
+        let post_filter = filter.requires_decoration();
 
-            if !should_include {
-                return None;
-            }
+        let mut entries: Vec<DecoratedEntry> = paths
+            .into_par_iter()
+            .filter_map(|path| build_entry(args, path, dir_sizes))
+            .filter(|entry| post_filter || matches_filter(filter, entry))
+            .collect();
 
-            if args.include_dirs && metadata.is_dir {
-                if let Ok(dir_size) = calculate_dir_size(&path) {
-                    metadata.size = dir_size;
-                }
+        self.plugin_manager
+            .decorate_entries(&mut entries, self.decoration_format);
+
+        if post_filter {
+            if let Err(e) = filter.prepare(&entries, self.plugin_manager) {
+                eprintln!("⚠️ {}", e);
             }
+            entries.retain(|entry| matches_filter(filter, entry));
+        }
+
+        entries
+    }
+}
+
+impl Iterator for EntryStream<'_> {
+    type Item = DecoratedEntry;
 
-            if !filter
-                .filter_files(std::slice::from_ref(&path))
-                .map(|v| !v.is_empty())
-                .unwrap_or(false)
-            {
-                return None;
+    fn next(&mut self) -> Option<DecoratedEntry> {
+        loop {
+            if let Some(entry) = self.pending.next() {
+                return Some(entry);
             }
+            let paths = match self.batches.next() {
+                Some(paths) => paths,
+                None => {
+                    self.dir_sizes = self.size_walk.take()?.join().ok();
+                    std::mem::take(&mut self.held_dirs)
+                }
+            };
+            self.pending = self.process_batch(paths).into_iter();
+        }
+    }
+}
+
+/// Lists a directory with the total size of its contents, both apparent and
+/// allocated.
+fn set_dir_size(
+    metadata: &mut EntryMetadata,
+    custom_fields: &mut HashMap<String, String>,
+    size: DirSize,
+) {
+    metadata.size = size.apparent;
+    custom_fields.insert(ALLOCATED_SIZE_FIELD.to_string(), size.allocated.to_string());
+}
+
+fn build_entry(args: &Args, path: PathBuf, dir_sizes: Option<&DirSizes>) -> Option<DecoratedEntry> {
+    let fs_metadata = match path.symlink_metadata() {
+        Ok(meta) => meta,
+        Err(_) => {
+            if let Some(file_name) = path.file_name() {
+                let mut custom_fields = HashMap::new();
+                custom_fields.insert("invalid_symlink".to_string(), "true".to_string());
 
-            let mut custom_fields = HashMap::new();
-            if metadata.is_symlink {
                 if let Ok(target) = std::fs::read_link(&path) {
                     custom_fields.insert(
                         "symlink_target".to_string(),
                         target.to_string_lossy().into_owned(),
                     );
                 }
+
+                return Some(DecoratedEntry {
+                    path: path.to_string_lossy().into_owned(),
+                    metadata: Some(EntryMetadata {
+                        size: 0,
+                        modified: 0,
+                        accessed: 0,
+                        created: 0,
+                        is_dir: false,
+                        is_file: false,
+                        is_symlink: true,
+                        permissions: 0,
+                        uid: 0,
+                        gid: 0,
+                    }),
+                    custom_fields,
+                });
             }
+            return None;
+        }
+    };
 
-            Some(DecoratedEntry {
-                path: path.to_string_lossy().into_owned(),
-                metadata: Some(metadata),
-                custom_fields,
-            })
-        })
-        .collect();
+    let mut metadata = convert_metadata(&fs_metadata);
+
+    let is_dotfile = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.starts_with('.'))
+        .unwrap_or(false);
+
+    let is_current_or_parent_dir = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n == "." || n == "..")
+        .unwrap_or(false);
+
+    if args.dotfiles_only && !is_dotfile {
+        return None;
+    } else if args.no_dotfiles && is_dotfile {
+        return None;
+    } else if args.almost_all && is_current_or_parent_dir {
+        return None;
+    }
+
+    let should_include = if args.dirs_only {
+        metadata.is_dir
+    } else if args.files_only {
+        metadata.is_file
+    } else if args.symlinks_only {
+        metadata.is_symlink && !args.no_symlinks
+    } else {
+        let include_dirs = !args.no_dirs;
+        let include_files = !args.no_files;
+        let include_symlinks = !args.no_symlinks;
+
+        (metadata.is_dir && include_dirs)
+            || (metadata.is_file && include_files)
+            || (metadata.is_symlink && include_symlinks)
+    };
 
-    for entry in &mut entries {
-        plugin_manager.decorate_entry(entry, format);
+    if !should_include {
+        return None;
     }
 
-    Ok(entries)
+    let mut custom_fields = HashMap::new();
+    if metadata.is_dir {
+        if let Some(size) = dir_sizes.and_then(|sizes| sizes.get(&fs_metadata)) {
+            set_dir_size(&mut metadata, &mut custom_fields, size);
+        }
+    }
+    if metadata.is_symlink {
+        if let Ok(target) = std::fs::read_link(&path) {
+            custom_fields.insert(
+                "symlink_target".to_string(),
+                target.to_string_lossy().into_owned(),
+            );
+        }
+    }
+
+    let entry = DecoratedEntry {
+        path: path.to_string_lossy().into_owned(),
+        metadata: Some(metadata),
+        custom_fields,
+    };
+
+    Some(entry)
 }
 
 pub fn list_and_decorate_archive_entries(
@@ -425,7 +477,7 @@ pub fn list_and_decorate_archive_entries(
 
     // Filter and options
     let mut filtered: Vec<DecoratedEntry> = Vec::with_capacity(entries.len());
-    for mut entry in entries.into_iter() {
+    for entry in entries.into_iter() {
         let pb = PathBuf::from(&entry.path);
 
         // Exclude synthetic root from all views
@@ -497,19 +549,15 @@ pub fn list_and_decorate_archive_entries(
             continue;
         }
 
-        // Apply name/path filters
-        if !filter
-            .filter_files(std::slice::from_ref(&pb))
-            .map(|v| !v.is_empty())
-            .unwrap_or(false)
-        {
-            continue;
-        }
-
-        plugin_manager.decorate_entry(&mut entry, format);
         filtered.push(entry);
     }
 
+    plugin_manager.decorate_entries(&mut filtered, decoration_format(filter, format));
+
+    // Apply name/path, metadata and plugin field filters
+    filter.prepare(&filtered, plugin_manager)?;
+    filtered.retain(|entry| matches_filter(filter, entry));
+
     Ok(filtered)
 }
 
@@ -525,26 +573,16 @@ pub fn list_and_decorate_single_file(
     let path = Path::new(&args.directory);
     let mut entries: Vec<DecoratedEntry> = Vec::with_capacity(1);
 
-    // Apply filter against this single path
-    if !filter
-        .filter_files(std::slice::from_ref(&path.to_path_buf()))
-        .map(|v| !v.is_empty())
-        .unwrap_or(false)
-    {
-        return Ok(entries);
-    }
-
     // Read metadata and map to EntryMetadata
     let fs_metadata = path.symlink_metadata()?;
     let mut metadata = convert_metadata(&fs_metadata);
 
-    if args.include_dirs && metadata.is_dir {
-        if let Ok(dir_size) = calculate_dir_size(path) {
-            metadata.size = dir_size;
+    let mut custom_fields = HashMap::new();
+    if wants_dir_sizes(args) && metadata.is_dir {
+        if let Some(size) = DirSizes::compute(path, args.one_file_system).get(&fs_metadata) {
+            set_dir_size(&mut metadata, &mut custom_fields, size);
         }
     }
-
-    let mut custom_fields = HashMap::new();
     if metadata.is_symlink {
         if let Ok(target) = fs::read_link(path) {
             custom_fields.insert(
@@ -560,16 +598,42 @@ pub fn list_and_decorate_single_file(
         custom_fields,
     };
 
-    plugin_manager.decorate_entry(&mut entry, format);
+    plugin_manager.decorate_entry(&mut entry, decoration_format(filter, format));
+
+    filter.prepare(std::slice::from_ref(&entry), plugin_manager)?;
+    if !matches_filter(filter, &entry) {
+        return Ok(entries);
+    }
+
     entries.push(entry);
     Ok(entries)
 }
 
+/// Field filters need plugin fields even in views that aren't decorated
+/// otherwise, so those fall back to the decorations of the default view.
+fn decoration_format<'a>(filter: &Arc<dyn FileFilter + Send + Sync>, format: &'a str) -> &'a str {
+    if filter.requires_decoration() && !PluginManager::decorates_format(format) {
+        "default"
+    } else {
+        format
+    }
+}
+
+fn matches_filter(filter: &Arc<dyn FileFilter + Send + Sync>, entry: &DecoratedEntry) -> bool {
+    filter
+        .filter_entries(std::slice::from_ref(entry))
+        .map(|v| !v.is_empty())
+        .unwrap_or(false)
+}
+
 pub fn sort_files(
     files: Vec<DecoratedEntry>,
     sorter: &Arc<dyn FileSorter + Send + Sync>,
     args: &Args,
+    plugin_manager: &mut PluginManager,
 ) -> Result<Vec<DecoratedEntry>> {
+    sorter.prepare(&files, plugin_manager)?;
+
     let mut entries_with_paths: Vec<(PathBuf, &DecoratedEntry)> = files
         .iter()
         .map(|entry| (PathBuf::from(&entry.path), entry))
@@ -596,110 +660,246 @@ pub fn sort_files(
 pub fn create_lister(args: &Args) -> Arc<dyn FileLister + Send + Sync> {
     if args.fuzzy_format {
         let config = Config::load(&Config::get_config_path()).unwrap_or_default();
-        Arc::new(FuzzyLister::new(config))
+        Arc::new(FuzzyLister::new(config, args.respect_gitignore))
     } else if args.tree_format || args.recursive_format {
         let config = Config::load(&Config::get_config_path()).unwrap_or_default();
-        Arc::new(RecursiveLister::new(config))
+        Arc::new(RecursiveLister::new(config, args.respect_gitignore))
     } else {
-        Arc::new(BasicLister)
+        Arc::new(BasicLister::new(args.respect_gitignore))
     }
 }
 
-pub fn create_sorter(args: &Args) -> Arc<dyn FileSorter + Send + Sync> {
-    let sorter: Arc<dyn FileSorter + Send + Sync> = match args.sort_by.as_str() {
-        "name" => Arc::new(AlphabeticalSorter),
+/// Creates the sorter for `--sort` or `default_sort`. Plugin sorters are only
+/// known once plugins are loaded, so unknown keys are caught here: in `--sort`
+/// they are a usage error, in `default_sort` the listing is sorted by name.
+fn sorter_for_args(
+    args: &Args,
+    plugin_manager: &PluginManager,
+) -> Result<Arc<dyn FileSorter + Send + Sync>> {
+    match create_sorter(&args.sort_by, plugin_manager) {
+        Ok(sorter) => Ok(sorter),
+        Err(e) if args.sort_from_config => {
+            eprintln!("Warning: default_sort: {}; sorting by name", e);
+            create_sorter("name", plugin_manager)
+        }
+        Err(e) => clap::Error::raw(
+            clap::ErrorKind::InvalidValue,
+            format!(
+                "Invalid value \"{}\" for '--sort <sort>': {}\n\nFor more information try --help\n",
+                args.sort_by, e
+            ),
+        )
+        .exit(),
+    }
+}
+
+pub fn create_sorter(
+    spec: &str,
+    plugin_manager: &PluginManager,
+) -> Result<Arc<dyn FileSorter + Send + Sync>> {
+    let mut keys = Vec::new();
+    for (key, direction) in parse_sort_spec(spec) {
+        let sorter = create_key_sorter(key, plugin_manager)?;
+        let reverse = match direction {
+            Some(SortDirection::Asc) => sorter.descending_by_default(),
+            Some(SortDirection::Desc) => !sorter.descending_by_default(),
+            None => false,
+        };
+        keys.push((sorter, reverse));
+    }
+
+    if keys.len() == 1 && !keys[0].1 {
+        return Ok(keys.remove(0).0);
+    }
+    Ok(Arc::new(MultiSorter::new(keys)))
+}
+
+fn create_key_sorter(
+    key: &str,
+    plugin_manager: &PluginManager,
+) -> Result<Arc<dyn FileSorter + Send + Sync>> {
+    let key = canonical_key(key);
+    let sorter: Arc<dyn FileSorter + Send + Sync> = match key {
+        "name" => Arc::new(AlphabeticalSorter::default()),
+        "version" => Arc::new(AlphabeticalSorter::version()),
         "size" => Arc::new(SizeSorter),
-        "date" => Arc::new(DateSorter),
-        _ => Arc::new(AlphabeticalSorter),
+        "date" => Arc::new(DateSorter::new(TimeKey::Modified)),
+        "accessed" => Arc::new(DateSorter::new(TimeKey::Accessed)),
+        "created" => Arc::new(DateSorter::new(TimeKey::Created)),
+        "extension" => Arc::new(ExtensionSorter),
+        "kind" => Arc::new(KindSorter),
+        "owner" => Arc::new(OwnerSorter::new(OwnerKey::User)),
+        "group" => Arc::new(OwnerSorter::new(OwnerKey::Group)),
+        "permissions" => Arc::new(PermissionsSorter),
+        "inode" => Arc::new(InodeSorter::default()),
+        _ => match key.strip_prefix("field:") {
+            Some(field) if !field.is_empty() => Arc::new(FieldSorter::new(field.to_string())),
+            _ => match plugin_manager.sorter_provider(key) {
+                Some(plugin) => Arc::new(PluginSorter::new(plugin, key.to_string())),
+                None => {
+                    let plugin_sorters = plugin_manager.sorter_names();
+                    return Err(LlaError::Other(format!(
+                        "unknown sort key '{}', expected one of {}{} or field:<name>",
+                        key,
+                        SORT_KEYS.join(", "),
+                        if plugin_sorters.is_empty() {
+                            String::new()
+                        } else {
+                            format!(", {}", plugin_sorters.join(", "))
+                        }
+                    )));
+                }
+            },
+        },
     };
 
-    sorter
+    Ok(sorter)
 }
 
-pub fn create_filter(args: &Args) -> Arc<dyn FileFilter + Send + Sync> {
+pub fn create_filter(
+    args: &Args,
+    plugin_manager: &PluginManager,
+) -> Result<Arc<dyn FileFilter + Send + Sync>> {
     match &args.filter {
-        Some(filter_str) => {
-            if filter_str.contains(" AND ") {
-                let mut composite = CompositeFilter::new(FilterOperation::And);
-                for part in filter_str.split(" AND ") {
-                    composite.add_filter(create_base_filter(part.trim(), !args.case_sensitive));
-                }
-                Arc::new(composite)
-            } else if filter_str.contains(" OR ") {
-                let mut composite = CompositeFilter::new(FilterOperation::Or);
-                for part in filter_str.split(" OR ") {
-                    composite.add_filter(create_base_filter(part.trim(), !args.case_sensitive));
-                }
-                Arc::new(composite)
-            } else if filter_str.starts_with("NOT ") {
-                let mut composite = CompositeFilter::new(FilterOperation::Not);
-                composite.add_filter(create_base_filter(&filter_str[4..], !args.case_sensitive));
-                Arc::new(composite)
-            } else if filter_str.starts_with("XOR ") {
-                let mut composite = CompositeFilter::new(FilterOperation::Xor);
-                composite.add_filter(create_base_filter(&filter_str[4..], !args.case_sensitive));
-                Arc::new(composite)
-            } else {
-                Arc::from(create_base_filter(filter_str, !args.case_sensitive))
-            }
+        Some(filter_str) if !filter_str.trim().is_empty() => {
+            let case_insensitive = !args.case_sensitive;
+            let expr = FilterExpr::parse(filter_str)?;
+            let filter = expr.build(&|pattern: &str| {
+                create_base_filter(pattern, case_insensitive, plugin_manager)
+            })?;
+            Ok(Arc::from(filter))
         }
-        None => Arc::new(PatternFilter::new("".to_string())),
+        _ => Ok(Arc::new(PatternFilter::new("".to_string()))),
     }
 }
 
-fn create_base_filter(pattern: &str, case_insensitive: bool) -> Box<dyn FileFilter + Send + Sync> {
-    let base_filter: Box<dyn FileFilter + Send + Sync> = if pattern.starts_with("regex:") {
-        Box::new(RegexFilter::new(pattern[6..].to_string()))
-    } else if pattern.starts_with("glob:") {
-        Box::new(GlobFilter::new(pattern[5..].to_string()))
-    } else if pattern.starts_with('.') {
-        Box::new(ExtensionFilter::new(pattern[1..].to_string()))
-    } else {
-        Box::new(PatternFilter::new(pattern.to_string()))
-    };
+fn create_base_filter(
+    pattern: &str,
+    case_insensitive: bool,
+    plugin_manager: &PluginManager,
+) -> Result<Box<dyn FileFilter + Send + Sync>> {
+    if FieldFilter::is_predicate(pattern) {
+        return Ok(Box::new(FieldFilter::new(pattern)?));
+    }
+
+    if MetadataFilter::is_predicate(pattern) {
+        return Ok(Box::new(MetadataFilter::new(pattern)?));
+    }
+
+    // `<filter>:<argument>` for filters provided by enabled plugins, unless
+    // the prefix is one of the built-in ones below. Anything else, such as
+    // `TODO:x`, is a plain pattern.
+    if let Some((name, argument)) = pattern.split_once(':') {
+        if !matches!(name, "regex" | "glob") {
+            if let Some(plugin) = plugin_manager.filter_provider(name) {
+                return Ok(Box::new(PluginFilter::new(
+                    plugin,
+                    name.to_string(),
+                    argument.to_string(),
+                )));
+            }
+            if let Some(plugin) = plugin_manager.disabled_filter_provider(name) {
+                eprintln!(
+                    "Warning: filter '{}' is provided by plugin '{}', which isn't enabled; matching '{}' as a pattern",
+                    name, plugin, pattern
+                );
+            }
+        }
+    }
+
+    let base_filter: Box<dyn FileFilter + Send + Sync> =
+        if let Some(regex) = pattern.strip_prefix("regex:") {
+            Box::new(RegexFilter::new(regex.to_string())?)
+        } else if let Some(glob) = pattern.strip_prefix("glob:") {
+            Box::new(GlobFilter::new(glob.to_string())?)
+        } else if let Some(extension) = pattern.strip_prefix('.') {
+            Box::new(ExtensionFilter::new(extension.to_string()))
+        } else {
+            Box::new(PatternFilter::new(pattern.to_string()))
+        };
 
     if case_insensitive {
-        Box::new(CaseInsensitiveFilter::new(base_filter))
+        Ok(Box::new(CaseInsensitiveFilter::new(base_filter)))
     } else {
-        base_filter
+        Ok(base_filter)
     }
 }
 
 pub fn create_formatter(args: &Args) -> Box<dyn FileFormatter> {
-    if args.fuzzy_format {
+    if let Some(formatter) = &args.plugin_formatter {
+        Box::new(formatter.clone())
+    } else if args.fuzzy_format {
         Box::new(FuzzyFormatter::new(
             args.show_icons,
             args.permission_format.clone(),
         ))
+    } else if args.tree_format && args.long_format {
+        let mut columns = args.columns.clone().unwrap_or_else(|| {
+            vec![
+                Column::Permissions,
+                Column::Size,
+                Column::time(args.time_field),
+                Column::Git,
+            ]
+        });
+        if args.git_status {
+            columns::insert_git_column(&mut columns);
+        }
+        Box::new(TreeFormatter::new(args.show_icons).with_columns(
+            columns,
+            args.permission_format.clone(),
+            args.relative_dates,
+        ))
     } else if args.long_format {
-        Box::new(LongFormatter::new(
+        let mut formatter = LongFormatter::new(
             args.show_icons,
             args.permission_format.clone(),
             args.hide_group,
             args.relative_dates,
+            args.time_field,
+            args.columns.clone(),
+        );
+        if args.git_status {
+            columns::insert_git_column(&mut formatter.columns);
+        }
+        Box::new(formatter)
+    } else if args.tree_format && args.git_status {
+        Box::new(TreeFormatter::new(args.show_icons).with_columns(
+            vec![Column::Git],
+            args.permission_format.clone(),
+            args.relative_dates,
         ))
     } else if args.tree_format {
         Box::new(TreeFormatter::new(args.show_icons))
     } else if args.table_format {
-        Box::new(TableFormatter::new(
+        let mut formatter = TableFormatter::new(
             args.show_icons,
             args.permission_format.clone(),
-        ))
+            args.time_field,
+            args.columns.clone(),
+        );
+        if args.git_status {
+            columns::insert_git_column(&mut formatter.columns);
+        }
+        Box::new(formatter)
     } else if args.grid_format {
         let config = Config::load(&Config::get_config_path()).unwrap_or_default();
-        Box::new(GridFormatter::new(
-            args.show_icons,
-            args.grid_ignore || config.formatters.grid.ignore_width,
-            config.formatters.grid.max_width,
-        ))
+        Box::new(
+            GridFormatter::new(
+                args.show_icons,
+                args.grid_ignore || config.formatters.grid.ignore_width,
+                config.formatters.grid.max_width,
+            )
+            .with_git_status(args.git_status),
+        )
     } else if args.sizemap_format {
         Box::new(SizeMapFormatter::new(args.show_icons))
     } else if args.timeline_format {
-        Box::new(TimelineFormatter::new(args.show_icons))
+        Box::new(TimelineFormatter::new(args.show_icons, args.time_field))
     } else if args.git_format {
         Box::new(GitFormatter::new(args.show_icons))
     } else if args.recursive_format {
-        Box::new(RecursiveFormatter::new(args.show_icons))
+        Box::new(RecursiveFormatter::new(args.show_icons, args.time_field))
     } else {
         Box::new(DefaultFormatter::new(args.show_icons))
     }
@@ -1,4 +1,5 @@
 pub mod args;
 pub mod command_handler;
+pub mod du;
 pub mod file_utils;
 pub mod plugin_utils;
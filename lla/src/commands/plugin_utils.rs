@@ -13,11 +13,21 @@ pub fn list_plugins(plugin_manager: &mut PluginManager) -> Result<()> {
     let plugin_names: Vec<String> = plugins
         .iter()
         .map(|(name, version, desc)| {
+            let api = match plugin_manager.plugin_api(name) {
+                Some((api_version, capabilities)) if capabilities.is_empty() => {
+                    format!(" [api {}]", api_version)
+                }
+                Some((api_version, capabilities)) => {
+                    format!(" [api {}: {}]", api_version, capabilities.join(", "))
+                }
+                None => String::new(),
+            };
             format!(
-                "{} {} - {}",
+                "{} {} - {}{}",
                 name.cyan(),
                 format!("v{}", version).yellow(),
-                desc
+                desc,
+                api.bright_black()
             )
         })
         .collect();
@@ -64,7 +74,7 @@ pub fn handle_plugin_action(
     action: &str,
     args: &[String],
 ) -> Result<()> {
-    let mut plugin_manager = PluginManager::new(config.clone());
+    let mut plugin_manager = PluginManager::new(config.clone()).in_process();
     plugin_manager.discover_plugins(&config.plugins_dir)?;
     plugin_manager.perform_plugin_action(plugin_name, action, args)
 }
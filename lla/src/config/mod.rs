@@ -1,6 +1,10 @@
 use crate::commands::args::ConfigAction;
 use crate::error::{ConfigErrorKind, LlaError, Result};
+use crate::formatter::columns::parse_columns;
+use crate::sorter::{is_sort_spec, SORT_KEYS};
 use crate::theme::{load_theme, Theme};
+use crate::utils::size::SizeFormat;
+use crate::utils::time::{TimeField, TimeStyle, TIME_STYLES};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -73,6 +77,8 @@ pub struct LongFormatterConfig {
     pub hide_group: bool,
     #[serde(default)]
     pub relative_dates: bool,
+    #[serde(default)]
+    pub columns: Vec<String>,
 }
 
 impl Default for LongFormatterConfig {
@@ -80,6 +86,7 @@ impl Default for LongFormatterConfig {
         Self {
             hide_group: false,
             relative_dates: false,
+            columns: Vec::new(),
         }
     }
 }
@@ -156,6 +163,94 @@ pub struct FilterConfig {
     pub case_sensitive: bool,
     #[serde(default)]
     pub no_dotfiles: bool,
+    #[serde(default)]
+    pub respect_gitignore: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SizeConfig {
+    #[serde(default = "default_size_format")]
+    pub format: String,
+    #[serde(default = "default_size_precision")]
+    pub precision: usize,
+    #[serde(default)]
+    pub disk_usage: bool,
+    #[serde(default)]
+    pub one_file_system: bool,
+}
+
+impl Default for SizeConfig {
+    fn default() -> Self {
+        Self {
+            format: default_size_format(),
+            precision: default_size_precision(),
+            disk_usage: false,
+            one_file_system: false,
+        }
+    }
+}
+
+fn default_size_format() -> String {
+    "binary".to_string()
+}
+
+fn default_size_precision() -> usize {
+    1
+}
+
+pub const MAX_SIZE_PRECISION: usize = 6;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimeConfig {
+    #[serde(default = "default_time_style")]
+    pub style: String,
+    #[serde(default = "default_time_field")]
+    pub field: String,
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        Self {
+            style: default_time_style(),
+            field: default_time_field(),
+        }
+    }
+}
+
+fn default_time_style() -> String {
+    "default".to_string()
+}
+
+fn default_time_field() -> String {
+    "modified".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginsConfig {
+    #[serde(default)]
+    pub isolate: bool,
+    #[serde(default = "default_plugin_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_plugin_max_restarts")]
+    pub max_restarts: u32,
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        Self {
+            isolate: false,
+            timeout_ms: default_plugin_timeout_ms(),
+            max_restarts: default_plugin_max_restarts(),
+        }
+    }
+}
+
+fn default_plugin_timeout_ms() -> u64 {
+    10000
+}
+
+fn default_plugin_max_restarts() -> u32 {
+    2
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -175,6 +270,12 @@ pub struct Config {
     #[serde(default)]
     pub filter: FilterConfig,
     #[serde(default)]
+    pub size: SizeConfig,
+    #[serde(default)]
+    pub time: TimeConfig,
+    #[serde(default)]
+    pub plugins: PluginsConfig,
+    #[serde(default)]
     pub formatters: FormatterConfig,
     #[serde(default)]
     pub listers: ListerConfig,
@@ -258,8 +359,20 @@ impl Config {
 # Default sorting method for file listings
 # Possible values:
 #   - "name": Sort alphabetically by filename (default)
+#   - "version": Sort by version numbers in names (1.9.2 before 1.10.0-rc1
+#     before 1.10.0), like ls -v
 #   - "size": Sort by file size, largest first
-#   - "date": Sort by modification time, newest first
+#   - "date": Sort by modification time
+#   - "accessed", "created": Sort by access or creation time
+#   - "extension": Sort by file extension
+#   - "kind": Directories, symlinks, files, then other kinds
+#   - "owner", "group": Sort by owning user or group name
+#   - "permissions": Sort by permission bits
+#   - "inode": Sort by inode number
+#   - "field:<name>": Sort by a plugin field, e.g. "field:complexity"
+#   - Sorters provided by enabled plugins, e.g. "complexity"
+# Several keys can be combined, each optionally suffixed with :asc or :desc,
+# e.g. "extension,size:desc,name" groups by extension, then largest first
 default_sort = "{}"
 
 # Default format for displaying files
@@ -343,6 +456,72 @@ case_sensitive = {}
 # Default: false
 no_dotfiles = {}
 
+# Hide entries ignored by .gitignore, .ignore, global git excludes
+# and .git/info/exclude (use --no-ignore to show them anyway)
+# Default: false
+respect_gitignore = {}
+
+# Size display configuration
+[size]
+# How sizes are shown
+# Possible values:
+#   - "binary": Powers of 1024 (e.g., 1.5K, 20.0M)
+#   - "si": Powers of 1000 (e.g., 1.5k, 21.0M)
+#   - "bytes": Exact number of bytes
+#   - "blocks": Number of 1 KiB blocks
+# Default: "binary"
+format = "{}"
+
+# Number of decimal places for binary and si sizes
+# Default: 1
+precision = {}
+
+# Show the space allocated on disk instead of the apparent file size
+# Only changes the sizes shown; sorting, filters and machine output keep
+# using the apparent size
+# Default: false
+disk_usage = {}
+
+# Don't count directories on other filesystems (mount points) in
+# directory sizes, like du -x
+# Default: false
+one_file_system = {}
+
+# Timestamp display configuration
+[time]
+# How timestamps are shown
+# Possible values:
+#   - "default": Each view's built-in format
+#   - "iso": 10-21 14:05 for recent files, 2023-10-21 for older ones
+#   - "long-iso": 2024-10-21 14:05
+#   - "full-iso": 2024-10-21 14:05:09 +0200
+#   - "relative": 2 hours ago
+#   - A strftime format starting with +, e.g. "+%Y-%m-%d %H:%M"
+# Default: "default"
+style = "{}"
+
+# Which timestamp long, table, timeline and recursive views show
+# Possible values: "modified", "accessed", "created", "changed"
+# Default: "modified"
+field = "{}"
+
+# Plugin host configuration
+[plugins]
+# Run library plugins in a separate `lla plugin-host` process, so a plugin
+# that crashes only loses its fields instead of stopping lla
+# Executables in the plugins directory always run as separate processes
+# Default: false
+isolate = {}
+
+# Milliseconds to wait for a plugin process to answer a request
+# Default: 10000
+timeout_ms = {}
+
+# How often a plugin process that crashed or timed out is restarted before
+# it is disabled for the rest of the run
+# Default: 2
+max_restarts = {}
+
 # Formatter-specific configurations
 [formatters.tree]
 # Maximum number of entries to display in tree view
@@ -373,6 +552,13 @@ hide_group = {}
 # Default: false
 relative_dates = {}
 
+# Columns shown in long and table formats, in order
+# Available: perms, size, mtime, atime, btime, ctime, inode, links, owner, group, git, name
+# Plugin fields can be shown as columns with field:<name>, e.g. "field:tags"
+# Example: ["perms", "size", "mtime", "owner", "git", "field:tags", "name"]
+# Default: [] (the built-in layout of each format)
+columns = {}
+
 # Lister-specific configurations
 [listers.recursive]
 # Maximum number of entries to process in recursive listing
@@ -407,11 +593,22 @@ ignore_patterns = {}"#,
             self.sort.natural,
             self.filter.case_sensitive,
             self.filter.no_dotfiles,
+            self.filter.respect_gitignore,
+            self.size.format,
+            self.size.precision,
+            self.size.disk_usage,
+            self.size.one_file_system,
+            self.time.style,
+            self.time.field,
+            self.plugins.isolate,
+            self.plugins.timeout_ms,
+            self.plugins.max_restarts,
             self.formatters.tree.max_lines.unwrap_or(0),
             self.formatters.grid.ignore_width,
             self.formatters.grid.max_width,
             self.formatters.long.hide_group,
             self.formatters.long.relative_dates,
+            serde_json::to_string(&self.formatters.long.columns).unwrap(),
             self.listers.recursive.max_entries.unwrap_or(0),
             serde_json::to_string(&self.listers.fuzzy.ignore_patterns).unwrap(),
         );
@@ -514,16 +711,71 @@ ignore_patterns = {}"#,
     }
 
     pub fn validate(&self) -> Result<()> {
-        if !["name", "size", "date"].contains(&self.default_sort.as_str()) {
+        if !is_sort_spec(&self.default_sort) {
             return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
                 "default_sort".to_string(),
                 format!(
-                    "Invalid sort value: {}. Must be one of: name, size, date",
-                    self.default_sort
+                    "Invalid sort value: {}. Must be comma-separated keys from: {}, field:<name> or plugin sorters, each optionally followed by :asc or :desc",
+                    self.default_sort,
+                    SORT_KEYS.join(", ")
+                ),
+            )));
+        }
+
+        if let Err(msg) = parse_columns(&self.formatters.long.columns) {
+            return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                "formatters.long.columns".to_string(),
+                msg,
+            )));
+        }
+
+        if SizeFormat::parse(&self.size.format).is_none() {
+            return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                "size.format".to_string(),
+                format!(
+                    "Invalid size format: {}. Must be one of: {}",
+                    self.size.format,
+                    SizeFormat::NAMES.join(", ")
+                ),
+            )));
+        }
+
+        if self.size.precision > MAX_SIZE_PRECISION {
+            return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                "size.precision".to_string(),
+                format!("Precision must not exceed {}", MAX_SIZE_PRECISION),
+            )));
+        }
+
+        if TimeStyle::parse(&self.time.style).is_none() {
+            return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                "time.style".to_string(),
+                format!(
+                    "Invalid time style: {}. Must be one of: {} or a strftime format starting with +",
+                    self.time.style,
+                    TIME_STYLES.join(", ")
+                ),
+            )));
+        }
+
+        if TimeField::parse(&self.time.field).is_none() {
+            return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                "time.field".to_string(),
+                format!(
+                    "Invalid time field: {}. Must be one of: {}",
+                    self.time.field,
+                    TimeField::NAMES.join(", ")
                 ),
             )));
         }
 
+        if self.plugins.timeout_ms == 0 {
+            return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                "plugins.timeout_ms".to_string(),
+                "Timeout must be greater than 0".to_string(),
+            )));
+        }
+
         let valid_formats = [
             "default", "long", "tree", "grid", "git", "timeline", "sizemap", "table", "fuzzy",
         ];
@@ -579,6 +831,7 @@ ignore_patterns = {}"#,
                 format!("{}.dll", plugin),
                 format!("{}.dylib", plugin),
                 format!("{}.so", plugin),
+                format!("{}.lla-plugin", plugin),
                 plugin.clone(),
             ];
 
@@ -630,10 +883,13 @@ ignore_patterns = {}"#,
                 self.plugins_dir = new_dir;
             }
             ["default_sort"] => {
-                if !["name", "size", "date"].contains(&value) {
+                if !is_sort_spec(value) {
                     return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
                         key.to_string(),
-                        "must be one of: name, size, date".to_string(),
+                        format!(
+                            "must be comma-separated keys from: {}, field:<name> or plugin sorters, each optionally followed by :asc or :desc",
+                            SORT_KEYS.join(", ")
+                        ),
                     )));
                 }
                 self.default_sort = value.to_string();
@@ -726,6 +982,106 @@ ignore_patterns = {}"#,
                     ))
                 })?;
             }
+            ["filter", "respect_gitignore"] => {
+                self.filter.respect_gitignore = value.parse().map_err(|_| {
+                    LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        "must be true or false".to_string(),
+                    ))
+                })?;
+            }
+            ["size", "format"] => {
+                if SizeFormat::parse(value).is_none() {
+                    return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        format!("must be one of: {}", SizeFormat::NAMES.join(", ")),
+                    )));
+                }
+                self.size.format = value.to_string();
+            }
+            ["size", "precision"] => {
+                let precision = value.parse().map_err(|_| {
+                    LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        "must be a number".to_string(),
+                    ))
+                })?;
+                if precision > MAX_SIZE_PRECISION {
+                    return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        format!("should not exceed {}", MAX_SIZE_PRECISION),
+                    )));
+                }
+                self.size.precision = precision;
+            }
+            ["size", "disk_usage"] => {
+                self.size.disk_usage = value.parse().map_err(|_| {
+                    LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        "must be true or false".to_string(),
+                    ))
+                })?;
+            }
+            ["size", "one_file_system"] => {
+                self.size.one_file_system = value.parse().map_err(|_| {
+                    LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        "must be true or false".to_string(),
+                    ))
+                })?;
+            }
+            ["time", "style"] => {
+                if TimeStyle::parse(value).is_none() {
+                    return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        format!(
+                            "must be one of: {} or a strftime format starting with +",
+                            TIME_STYLES.join(", ")
+                        ),
+                    )));
+                }
+                self.time.style = value.to_string();
+            }
+            ["time", "field"] => {
+                if TimeField::parse(value).is_none() {
+                    return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        format!("must be one of: {}", TimeField::NAMES.join(", ")),
+                    )));
+                }
+                self.time.field = value.to_string();
+            }
+            ["plugins", "isolate"] => {
+                self.plugins.isolate = value.parse().map_err(|_| {
+                    LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        "must be true or false".to_string(),
+                    ))
+                })?;
+            }
+            ["plugins", "timeout_ms"] => {
+                let timeout_ms = value.parse().map_err(|_| {
+                    LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        "must be a number".to_string(),
+                    ))
+                })?;
+                if timeout_ms == 0 {
+                    return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        "must be greater than 0".to_string(),
+                    )));
+                }
+                self.plugins.timeout_ms = timeout_ms;
+            }
+            ["plugins", "max_restarts"] => {
+                self.plugins.max_restarts = value.parse().map_err(|_| {
+                    LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        "must be a number".to_string(),
+                    ))
+                })?;
+            }
             ["formatters", "tree", "max_lines"] => {
                 let max_lines = value.parse().map_err(|_| {
                     LlaError::Config(ConfigErrorKind::InvalidValue(
@@ -741,6 +1097,17 @@ ignore_patterns = {}"#,
                 }
                 self.formatters.tree.max_lines = Some(max_lines);
             }
+            ["formatters", "long", "columns"] => {
+                let columns: Vec<String> = value
+                    .split(',')
+                    .map(|column| column.trim().to_string())
+                    .filter(|column| !column.is_empty())
+                    .collect();
+                parse_columns(&columns).map_err(|msg| {
+                    LlaError::Config(ConfigErrorKind::InvalidValue(key.to_string(), msg))
+                })?;
+                self.formatters.long.columns = columns;
+            }
 
             ["listers", "recursive", "max_entries"] => {
                 let max_entries = value.parse().map_err(|_| {
@@ -821,6 +1188,9 @@ impl Default for Config {
             include_dirs: false,
             sort: SortConfig::default(),
             filter: FilterConfig::default(),
+            size: SizeConfig::default(),
+            time: TimeConfig::default(),
+            plugins: PluginsConfig::default(),
             formatters: FormatterConfig::default(),
             listers: ListerConfig::default(),
             shortcuts: HashMap::new(),
@@ -1,5 +1,8 @@
 use super::FileFilter;
 use crate::error::Result;
+use crate::plugin::PluginManager;
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 pub struct CaseInsensitiveFilter {
@@ -40,4 +43,42 @@ impl FileFilter for CaseInsensitiveFilter {
             .map(|(_, path)| path.clone())
             .collect())
     }
+
+    fn filter_entries(&self, entries: &[DecoratedEntry]) -> Result<Vec<DecoratedEntry>> {
+        let lowercase_entries: Vec<DecoratedEntry> = entries
+            .iter()
+            .map(|entry| DecoratedEntry {
+                path: Self::to_lowercase_path(&PathBuf::from(&entry.path))
+                    .to_string_lossy()
+                    .into_owned(),
+                ..entry.clone()
+            })
+            .collect();
+
+        let filtered: HashSet<String> = self
+            .inner
+            .filter_entries(&lowercase_entries)?
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+
+        Ok(entries
+            .iter()
+            .zip(lowercase_entries.iter())
+            .filter(|(_, lowercase)| filtered.contains(&lowercase.path))
+            .map(|(entry, _)| entry.clone())
+            .collect())
+    }
+
+    fn requires_decoration(&self) -> bool {
+        self.inner.requires_decoration()
+    }
+
+    fn prepare(
+        &self,
+        entries: &[DecoratedEntry],
+        plugin_manager: &mut PluginManager,
+    ) -> Result<()> {
+        self.inner.prepare(entries, plugin_manager)
+    }
 }
@@ -1,5 +1,9 @@
 use super::FileFilter;
 use crate::error::{LlaError, Result};
+use crate::plugin::PluginManager;
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::collections::HashSet;
+use std::hash::Hash;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Copy)]
@@ -26,33 +30,36 @@ impl CompositeFilter {
     pub fn add_filter(&mut self, filter: Box<dyn FileFilter>) {
         self.filters.push(filter);
     }
-}
 
-impl FileFilter for CompositeFilter {
-    fn filter_files(&self, files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    fn combine<T, K, F, A>(&self, items: &[T], key: K, apply: A) -> Result<Vec<T>>
+    where
+        T: Clone,
+        F: Eq + Hash,
+        K: Fn(&T) -> F,
+        A: Fn(&dyn FileFilter, &[T]) -> Result<Vec<T>>,
+    {
         if self.filters.is_empty() {
-            return Ok(files.to_vec());
+            return Ok(items.to_vec());
         }
 
         match self.operation {
             FilterOperation::And => {
-                let mut result = files.to_vec();
+                let mut result = items.to_vec();
                 for filter in &self.filters {
-                    result = filter
-                        .filter_files(&result)
+                    result = apply(filter.as_ref(), &result)
                         .map_err(|e| LlaError::Filter(format!("AND operation failed: {}", e)))?;
                 }
                 Ok(result)
             }
             FilterOperation::Or => {
+                let mut seen = HashSet::new();
                 let mut result = Vec::new();
                 for filter in &self.filters {
-                    let filtered = filter
-                        .filter_files(files)
+                    let filtered = apply(filter.as_ref(), items)
                         .map_err(|e| LlaError::Filter(format!("OR operation failed: {}", e)))?;
-                    for file in filtered {
-                        if !result.contains(&file) {
-                            result.push(file);
+                    for item in filtered {
+                        if seen.insert(key(&item)) {
+                            result.push(item);
                         }
                     }
                 }
@@ -64,12 +71,14 @@ impl FileFilter for CompositeFilter {
                         "NOT operation requires exactly one filter".to_string(),
                     ));
                 }
-                let filtered = self.filters[0]
-                    .filter_files(files)
-                    .map_err(|e| LlaError::Filter(format!("NOT operation failed: {}", e)))?;
-                Ok(files
+                let filtered: HashSet<F> = apply(self.filters[0].as_ref(), items)
+                    .map_err(|e| LlaError::Filter(format!("NOT operation failed: {}", e)))?
                     .iter()
-                    .filter(|file| !filtered.contains(file))
+                    .map(&key)
+                    .collect();
+                Ok(items
+                    .iter()
+                    .filter(|item| !filtered.contains(&key(item)))
                     .cloned()
                     .collect())
             }
@@ -79,18 +88,27 @@ impl FileFilter for CompositeFilter {
                         "XOR operation requires exactly two filters".to_string(),
                     ));
                 }
-                let first = self.filters[0].filter_files(files).map_err(|e| {
-                    LlaError::Filter(format!("XOR operation failed on first filter: {}", e))
-                })?;
-                let second = self.filters[1].filter_files(files).map_err(|e| {
-                    LlaError::Filter(format!("XOR operation failed on second filter: {}", e))
-                })?;
+                let first: HashSet<F> = apply(self.filters[0].as_ref(), items)
+                    .map_err(|e| {
+                        LlaError::Filter(format!("XOR operation failed on first filter: {}", e))
+                    })?
+                    .iter()
+                    .map(&key)
+                    .collect();
+                let second: HashSet<F> = apply(self.filters[1].as_ref(), items)
+                    .map_err(|e| {
+                        LlaError::Filter(format!("XOR operation failed on second filter: {}", e))
+                    })?
+                    .iter()
+                    .map(&key)
+                    .collect();
 
-                Ok(files
+                Ok(items
                     .iter()
-                    .filter(|file| {
-                        let in_first = first.contains(file);
-                        let in_second = second.contains(file);
+                    .filter(|item| {
+                        let key = key(item);
+                        let in_first = first.contains(&key);
+                        let in_second = second.contains(&key);
                         in_first ^ in_second
                     })
                     .cloned()
@@ -99,3 +117,38 @@ impl FileFilter for CompositeFilter {
         }
     }
 }
+
+impl FileFilter for CompositeFilter {
+    fn filter_files(&self, files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        self.combine(
+            files,
+            |path| path.clone(),
+            |filter, items| filter.filter_files(items),
+        )
+    }
+
+    fn filter_entries(&self, entries: &[DecoratedEntry]) -> Result<Vec<DecoratedEntry>> {
+        self.combine(
+            entries,
+            |entry| entry.path.clone(),
+            |filter, items| filter.filter_entries(items),
+        )
+    }
+
+    fn requires_decoration(&self) -> bool {
+        self.filters
+            .iter()
+            .any(|filter| filter.requires_decoration())
+    }
+
+    fn prepare(
+        &self,
+        entries: &[DecoratedEntry],
+        plugin_manager: &mut PluginManager,
+    ) -> Result<()> {
+        for filter in &self.filters {
+            filter.prepare(entries, plugin_manager)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+use super::{CompositeFilter, FileFilter, FilterOperation};
+use crate::error::{LlaError, Result};
+
+/// Parsed form of a `--filter` expression.
+///
+/// Operator precedence, from tightest to loosest binding, is
+/// `NOT`, `AND`, `XOR`, `OR`. Parentheses group sub-expressions and
+/// single or double quotes allow terms containing spaces, parentheses or
+/// operator keywords.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterExpr {
+    Term(String),
+    Not(Box<FilterExpr>),
+    And(Vec<FilterExpr>),
+    Or(Vec<FilterExpr>),
+    Xor(Box<FilterExpr>, Box<FilterExpr>),
+}
+
+impl FilterExpr {
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(LlaError::Filter("filter expression is empty".to_string()));
+        }
+
+        let mut parser = Parser {
+            input,
+            tokens,
+            pos: 0,
+        };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(parser.error_at(
+                token,
+                &format!("unexpected {}, expected AND, OR or XOR", token.kind),
+            ));
+        }
+        Ok(expr)
+    }
+
+    /// Builds the filter tree, turning every term into a leaf filter with `leaf`.
+    pub fn build<F>(&self, leaf: &F) -> Result<Box<dyn FileFilter + Send + Sync>>
+    where
+        F: Fn(&str) -> Result<Box<dyn FileFilter + Send + Sync>>,
+    {
+        match self {
+            FilterExpr::Term(term) => leaf(term),
+            FilterExpr::Not(inner) => {
+                let mut composite = CompositeFilter::new(FilterOperation::Not);
+                composite.add_filter(inner.build(leaf)?);
+                Ok(Box::new(composite))
+            }
+            FilterExpr::And(operands) => Self::build_all(FilterOperation::And, operands, leaf),
+            FilterExpr::Or(operands) => Self::build_all(FilterOperation::Or, operands, leaf),
+            FilterExpr::Xor(left, right) => {
+                let mut composite = CompositeFilter::new(FilterOperation::Xor);
+                composite.add_filter(left.build(leaf)?);
+                composite.add_filter(right.build(leaf)?);
+                Ok(Box::new(composite))
+            }
+        }
+    }
+
+    fn build_all<F>(
+        operation: FilterOperation,
+        operands: &[FilterExpr],
+        leaf: &F,
+    ) -> Result<Box<dyn FileFilter + Send + Sync>>
+    where
+        F: Fn(&str) -> Result<Box<dyn FileFilter + Send + Sync>>,
+    {
+        let mut composite = CompositeFilter::new(operation);
+        for operand in operands {
+            composite.add_filter(operand.build(leaf)?);
+        }
+        Ok(Box::new(composite))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Term { text: String, quoted: bool },
+    And,
+    Or,
+    Not,
+    Xor,
+    LParen,
+    RParen,
+}
+
+impl std::fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenKind::Term { text, .. } => write!(f, "term '{}'", text),
+            TokenKind::And => write!(f, "AND"),
+            TokenKind::Or => write!(f, "OR"),
+            TokenKind::Not => write!(f, "NOT"),
+            TokenKind::Xor => write!(f, "XOR"),
+            TokenKind::LParen => write!(f, "'('"),
+            TokenKind::RParen => write!(f, "')'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+fn position(input: &str, byte_offset: usize) -> usize {
+    input[..byte_offset].chars().count() + 1
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        match c {
+            '(' | ')' => {
+                chars.next();
+                tokens.push(Token {
+                    kind: if c == '(' {
+                        TokenKind::LParen
+                    } else {
+                        TokenKind::RParen
+                    },
+                    start,
+                    end: start + 1,
+                });
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut text = String::new();
+                let mut end = None;
+                while let Some((i, ch)) = chars.next() {
+                    match ch {
+                        // Only quotes and backslashes are escaped, so regexes such as
+                        // `\d+` keep their backslashes.
+                        '\\' => match chars.peek() {
+                            Some(&(_, escaped @ ('"' | '\'' | '\\'))) => {
+                                chars.next();
+                                text.push(escaped);
+                            }
+                            _ => text.push('\\'),
+                        },
+                        ch if ch == c => {
+                            end = Some(i + ch.len_utf8());
+                            break;
+                        }
+                        ch => text.push(ch),
+                    }
+                }
+                let end = end.ok_or_else(|| {
+                    LlaError::Filter(format!(
+                        "unterminated quote starting at position {}",
+                        position(input, start)
+                    ))
+                })?;
+                tokens.push(Token {
+                    kind: TokenKind::Term { text, quoted: true },
+                    start,
+                    end,
+                });
+            }
+            _ => {
+                // Parentheses inside a term (e.g. `regex:^(foo|bar)$`) belong to the
+                // term as long as they are balanced.
+                let mut depth = 0usize;
+                let mut end = start;
+                while let Some(&(i, ch)) = chars.peek() {
+                    if ch.is_whitespace() || (ch == ')' && depth == 0) {
+                        break;
+                    }
+                    if ch == '(' {
+                        depth += 1;
+                    } else if ch == ')' {
+                        depth -= 1;
+                    }
+                    end = i + ch.len_utf8();
+                    chars.next();
+                }
+
+                let word = &input[start..end];
+                let kind = match word {
+                    "AND" => TokenKind::And,
+                    "OR" => TokenKind::Or,
+                    "NOT" => TokenKind::Not,
+                    "XOR" => TokenKind::Xor,
+                    _ => TokenKind::Term {
+                        text: word.to_string(),
+                        quoted: false,
+                    },
+                };
+                tokens.push(Token { kind, start, end });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next_if(&mut self, kind: &TokenKind) -> bool {
+        if self.peek().is_some_and(|t| &t.kind == kind) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error_at(&self, token: &Token, message: &str) -> LlaError {
+        LlaError::Filter(format!(
+            "invalid filter expression: {} at position {}",
+            message,
+            position(self.input, token.start)
+        ))
+    }
+
+    fn error_at_end(&self, message: &str) -> LlaError {
+        LlaError::Filter(format!(
+            "invalid filter expression: {} at end of input",
+            message
+        ))
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr> {
+        let mut operands = vec![self.parse_xor()?];
+        while self.next_if(&TokenKind::Or) {
+            operands.push(self.parse_xor()?);
+        }
+        Ok(if operands.len() == 1 {
+            operands.pop().unwrap()
+        } else {
+            FilterExpr::Or(operands)
+        })
+    }
+
+    fn parse_xor(&mut self) -> Result<FilterExpr> {
+        let mut expr = self.parse_and()?;
+        while self.next_if(&TokenKind::Xor) {
+            let right = self.parse_and()?;
+            expr = FilterExpr::Xor(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr> {
+        let mut operands = vec![self.parse_unary()?];
+        while self.next_if(&TokenKind::And) {
+            operands.push(self.parse_unary()?);
+        }
+        Ok(if operands.len() == 1 {
+            operands.pop().unwrap()
+        } else {
+            FilterExpr::And(operands)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterExpr> {
+        if self.next_if(&TokenKind::Not) {
+            return Ok(FilterExpr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<FilterExpr> {
+        let token = match self.peek() {
+            Some(token) => token.clone(),
+            None => return Err(self.error_at_end("expected a filter term")),
+        };
+
+        match token.kind {
+            TokenKind::LParen => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                match self.peek() {
+                    Some(t) if t.kind == TokenKind::RParen => {
+                        self.pos += 1;
+                        Ok(expr)
+                    }
+                    Some(t) => Err(self.error_at(
+                        t,
+                        &format!(
+                            "expected ')' to close '(' at position {}, found {}",
+                            position(self.input, token.start),
+                            t.kind
+                        ),
+                    )),
+                    None => Err(self.error_at_end(&format!(
+                        "missing ')' for '(' at position {}",
+                        position(self.input, token.start)
+                    ))),
+                }
+            }
+            TokenKind::Term { text, quoted } => {
+                self.pos += 1;
+                if quoted {
+                    return Ok(FilterExpr::Term(text));
+                }
+
+                // Unquoted words that follow each other without an operator form a
+                // single term, so `-f "my file"` keeps matching names with spaces.
+                let mut end = token.end;
+                while let Some(next) = self.peek() {
+                    match next.kind {
+                        TokenKind::Term { quoted: false, .. } => {
+                            end = next.end;
+                            self.pos += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                Ok(FilterExpr::Term(self.input[token.start..end].to_string()))
+            }
+            ref kind => {
+                Err(self.error_at(&token, &format!("expected a filter term, found {}", kind)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(text: &str) -> FilterExpr {
+        FilterExpr::Term(text.to_string())
+    }
+
+    fn error(input: &str) -> String {
+        match FilterExpr::parse(input) {
+            Err(LlaError::Filter(message)) => message,
+            other => panic!("expected a filter error for {:?}, got {:?}", input, other),
+        }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_xor_and_or() {
+        assert_eq!(
+            FilterExpr::parse("a OR b AND c XOR d").unwrap(),
+            FilterExpr::Or(vec![
+                term("a"),
+                FilterExpr::Xor(
+                    Box::new(FilterExpr::And(vec![term("b"), term("c")])),
+                    Box::new(term("d")),
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn not_applies_to_the_next_operand() {
+        assert_eq!(
+            FilterExpr::parse("NOT a AND b").unwrap(),
+            FilterExpr::And(vec![FilterExpr::Not(Box::new(term("a"))), term("b")])
+        );
+        assert_eq!(
+            FilterExpr::parse("NOT NOT a").unwrap(),
+            FilterExpr::Not(Box::new(FilterExpr::Not(Box::new(term("a")))))
+        );
+    }
+
+    #[test]
+    fn parentheses_group() {
+        assert_eq!(
+            FilterExpr::parse("(a OR b) AND NOT (c XOR d)").unwrap(),
+            FilterExpr::And(vec![
+                FilterExpr::Or(vec![term("a"), term("b")]),
+                FilterExpr::Not(Box::new(FilterExpr::Xor(
+                    Box::new(term("c")),
+                    Box::new(term("d")),
+                ))),
+            ])
+        );
+    }
+
+    #[test]
+    fn quotes_keep_spaces_parentheses_and_keywords() {
+        assert_eq!(
+            FilterExpr::parse(r#""my (old) file" OR 'AND'"#).unwrap(),
+            FilterExpr::Or(vec![term("my (old) file"), term("AND")])
+        );
+        assert_eq!(
+            FilterExpr::parse(r#""say \"hi\"""#).unwrap(),
+            term(r#"say "hi""#)
+        );
+        assert_eq!(
+            FilterExpr::parse(r#"'it\'s' OR "a\\b""#).unwrap(),
+            FilterExpr::Or(vec![term("it's"), term(r"a\b")])
+        );
+    }
+
+    #[test]
+    fn quoted_regex_keeps_backslashes() {
+        assert_eq!(
+            FilterExpr::parse(r#""regex:\d+ x\.txt$" AND NOT .md"#).unwrap(),
+            FilterExpr::And(vec![
+                term(r"regex:\d+ x\.txt$"),
+                FilterExpr::Not(Box::new(term(".md"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn unquoted_words_form_one_term() {
+        assert_eq!(FilterExpr::parse("my file").unwrap(), term("my file"));
+        assert_eq!(
+            FilterExpr::parse("regex:^(foo|bar)$ AND .rs").unwrap(),
+            FilterExpr::And(vec![term("regex:^(foo|bar)$"), term(".rs")])
+        );
+    }
+
+    #[test]
+    fn reports_unterminated_quotes() {
+        assert_eq!(
+            error(r#"a OR "b c"#),
+            "unterminated quote starting at position 6"
+        );
+        assert_eq!(error("'abc"), "unterminated quote starting at position 1");
+    }
+
+    #[test]
+    fn reports_unbalanced_parentheses() {
+        assert_eq!(
+            error("(a OR b"),
+            "invalid filter expression: missing ')' for '(' at position 1 at end of input"
+        );
+        assert_eq!(
+            error("a)"),
+            "invalid filter expression: unexpected ')', expected AND, OR or XOR at position 2"
+        );
+    }
+
+    #[test]
+    fn reports_missing_operands() {
+        assert_eq!(error(""), "filter expression is empty");
+        assert_eq!(
+            error("a AND"),
+            "invalid filter expression: expected a filter term at end of input"
+        );
+        assert_eq!(
+            error("OR a"),
+            "invalid filter expression: expected a filter term, found OR at position 1"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use super::FileFilter;
+use crate::error::{LlaError, Result};
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldOperation {
+    Exists,
+    Equals,
+    NotEquals,
+    ContainsItem,
+    ContainsText,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Filters entries on `custom_fields` provided by plugins, e.g.
+/// `field:tags~=urgent`, `field:category=Code` or
+/// `field:complexity_metrics.cyclomatic_complexity>10`.
+///
+/// Plugin fields only exist once entries are decorated, so this filter is
+/// evaluated after decoration.
+pub struct FieldFilter {
+    field: String,
+    operation: FieldOperation,
+    value: String,
+}
+
+impl FieldFilter {
+    pub fn is_predicate(pattern: &str) -> bool {
+        pattern.starts_with("field:")
+    }
+
+    pub fn new(pattern: &str) -> Result<Self> {
+        let spec = pattern.strip_prefix("field:").unwrap_or(pattern);
+
+        let operators = [
+            ("!=", FieldOperation::NotEquals),
+            ("~=", FieldOperation::ContainsItem),
+            ("*=", FieldOperation::ContainsText),
+            (">=", FieldOperation::Ge),
+            ("<=", FieldOperation::Le),
+            ("=", FieldOperation::Equals),
+            (">", FieldOperation::Gt),
+            ("<", FieldOperation::Lt),
+        ];
+
+        let found = spec
+            .char_indices()
+            .find_map(|(i, _)| {
+                operators
+                    .iter()
+                    .find(|(op, _)| spec[i..].starts_with(op))
+                    .map(|(op, operation)| (i, op.len(), *operation))
+            })
+            .map(|(i, len, operation)| (&spec[..i], operation, &spec[i + len..]));
+
+        let (field, operation, value) = found.unwrap_or((spec, FieldOperation::Exists, ""));
+        if field.is_empty() {
+            return Err(LlaError::Filter(format!(
+                "missing field name in '{}': expected e.g. field:tags~=urgent",
+                pattern
+            )));
+        }
+
+        if matches!(
+            operation,
+            FieldOperation::Lt | FieldOperation::Le | FieldOperation::Gt | FieldOperation::Ge
+        ) && value.parse::<f64>().is_err()
+        {
+            return Err(LlaError::Filter(format!(
+                "invalid number '{}' in '{}'",
+                value, pattern
+            )));
+        }
+
+        Ok(FieldFilter {
+            field: field.to_string(),
+            operation,
+            value: value.to_string(),
+        })
+    }
+
+    fn matches(&self, entry: &DecoratedEntry) -> bool {
+        let field = match lookup_field(entry, &self.field) {
+            Some(field) => field,
+            None => return self.operation == FieldOperation::NotEquals,
+        };
+
+        match self.operation {
+            FieldOperation::Exists => !field.is_empty(),
+            FieldOperation::Equals => field.eq_ignore_ascii_case(&self.value),
+            FieldOperation::NotEquals => !field.eq_ignore_ascii_case(&self.value),
+            FieldOperation::ContainsItem => field
+                .split(',')
+                .any(|item| item.trim().eq_ignore_ascii_case(&self.value)),
+            FieldOperation::ContainsText => {
+                field.to_lowercase().contains(&self.value.to_lowercase())
+            }
+            FieldOperation::Lt | FieldOperation::Le | FieldOperation::Gt | FieldOperation::Ge => {
+                let (Some(left), Ok(right)) = (leading_number(&field), self.value.parse::<f64>())
+                else {
+                    return false;
+                };
+                match self.operation {
+                    FieldOperation::Lt => left < right,
+                    FieldOperation::Le => left <= right,
+                    FieldOperation::Gt => left > right,
+                    _ => left >= right,
+                }
+            }
+        }
+    }
+}
+
+/// Resolves `name` in the entry's custom fields. Dotted names that don't match
+/// a field directly look inside structured (TOML or JSON) field values.
+pub(crate) fn lookup_field(entry: &DecoratedEntry, name: &str) -> Option<String> {
+    if let Some(value) = entry.custom_fields.get(name) {
+        return Some(value.clone());
+    }
+
+    let (base, path) = name.split_once('.')?;
+    let raw = entry.custom_fields.get(base)?;
+
+    if let Ok(table) = toml::from_str::<toml::Value>(raw) {
+        let mut value = &table;
+        for key in path.split('.') {
+            value = value.get(key)?;
+        }
+        return Some(match value {
+            toml::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        });
+    }
+
+    let json = serde_json::from_str::<serde_json::Value>(raw).ok()?;
+    let mut value = &json;
+    for key in path.split('.') {
+        value = value.get(key)?;
+    }
+    Some(match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    })
+}
+
+/// Plugins often decorate numbers, e.g. `[3 snippets]` or `12.5 KB`, so
+/// numeric comparisons use the first number found in the value.
+pub(crate) fn leading_number(value: &str) -> Option<f64> {
+    let start = value.find(|c: char| c.is_ascii_digit() || c == '-')?;
+    let rest = &value[start..];
+    let end = rest
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| !c.is_ascii_digit() && *c != '.')
+        .map_or(rest.len(), |(i, _)| i);
+    rest[..end].parse().ok()
+}
+
+impl FileFilter for FieldFilter {
+    fn filter_files(&self, _files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        Err(LlaError::Filter(format!(
+            "field:{} can only be evaluated on decorated entries",
+            self.field
+        )))
+    }
+
+    fn filter_entries(&self, entries: &[DecoratedEntry]) -> Result<Vec<DecoratedEntry>> {
+        Ok(entries
+            .iter()
+            .filter(|entry| self.matches(entry))
+            .cloned()
+            .collect())
+    }
+
+    fn requires_decoration(&self) -> bool {
+        true
+    }
+}
@@ -1,5 +1,5 @@
 use super::FileFilter;
-use crate::error::Result;
+use crate::error::{LlaError, Result};
 use glob::Pattern;
 use std::path::PathBuf;
 
@@ -8,10 +8,10 @@ pub struct GlobFilter {
 }
 
 impl GlobFilter {
-    pub fn new(pattern: String) -> Self {
-        GlobFilter {
-            pattern: Pattern::new(&pattern).unwrap_or_else(|_| Pattern::new("*").unwrap()),
-        }
+    pub fn new(pattern: String) -> Result<Self> {
+        let pattern = Pattern::new(&pattern)
+            .map_err(|e| LlaError::Filter(format!("invalid glob pattern '{}': {}", pattern, e)))?;
+        Ok(GlobFilter { pattern })
     }
 }
 
@@ -0,0 +1,367 @@
+use super::FileFilter;
+use crate::error::{LlaError, Result};
+use crate::utils::metadata::convert_metadata;
+use chrono::{Local, NaiveDate, TimeZone};
+use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use users::{get_group_by_name, get_user_by_name};
+
+const KEYS: [&str; 9] = [
+    "size", "modified", "accessed", "created", "owner", "user", "group", "perm", "type",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+impl Comparison {
+    fn split(value: &str) -> (Option<Self>, &str) {
+        for (prefix, comparison) in [
+            (">=", Comparison::Ge),
+            ("<=", Comparison::Le),
+            (">", Comparison::Gt),
+            ("<", Comparison::Lt),
+            ("=", Comparison::Eq),
+        ] {
+            if let Some(rest) = value.strip_prefix(prefix) {
+                return (Some(comparison), rest);
+            }
+        }
+        (None, value)
+    }
+
+    fn flipped(self) -> Self {
+        match self {
+            Comparison::Lt => Comparison::Gt,
+            Comparison::Le => Comparison::Ge,
+            Comparison::Gt => Comparison::Lt,
+            Comparison::Ge => Comparison::Le,
+            Comparison::Eq => Comparison::Eq,
+        }
+    }
+
+    fn compare(self, left: u64, right: u64) -> bool {
+        match self {
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+            Comparison::Eq => left == right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TimeField {
+    Modified,
+    Accessed,
+    Created,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum EntryKind {
+    File,
+    Dir,
+    Symlink,
+    Other,
+}
+
+#[derive(Debug, Clone)]
+enum PermissionMatch {
+    Exact(u32),
+    Symbolic {
+        shifts: Vec<u32>,
+        any_class: bool,
+        set: bool,
+        bits: u32,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum MetadataPredicate {
+    Size(Comparison, u64),
+    /// Matches timestamps in the half-open range `[start, end)`.
+    Time {
+        field: TimeField,
+        start: u64,
+        end: u64,
+    },
+    Owner(u32),
+    Group(u32),
+    Permissions(PermissionMatch),
+    Type(EntryKind),
+}
+
+/// Filters entries on their metadata, e.g. `size:>10M`, `modified:<7d`,
+/// `owner:alice`, `perm:+x` or `type:symlink`.
+pub struct MetadataFilter {
+    predicate: MetadataPredicate,
+}
+
+impl MetadataFilter {
+    pub fn is_predicate(pattern: &str) -> bool {
+        pattern
+            .split_once(':')
+            .is_some_and(|(key, _)| KEYS.contains(&key))
+    }
+
+    pub fn new(pattern: &str) -> Result<Self> {
+        let (key, value) = pattern
+            .split_once(':')
+            .ok_or_else(|| LlaError::Filter(format!("invalid predicate '{}'", pattern)))?;
+        let value = value.trim();
+
+        let predicate = match key {
+            "size" => {
+                let (comparison, size) = Comparison::split(value);
+                MetadataPredicate::Size(comparison.unwrap_or(Comparison::Eq), parse_size(size)?)
+            }
+            "modified" => parse_time(TimeField::Modified, value)?,
+            "accessed" => parse_time(TimeField::Accessed, value)?,
+            "created" => parse_time(TimeField::Created, value)?,
+            "owner" | "user" => MetadataPredicate::Owner(match value.parse() {
+                Ok(uid) => uid,
+                Err(_) => get_user_by_name(value)
+                    .map(|u| u.uid())
+                    .ok_or_else(|| LlaError::Filter(format!("unknown user '{}'", value)))?,
+            }),
+            "group" => MetadataPredicate::Group(match value.parse() {
+                Ok(gid) => gid,
+                Err(_) => get_group_by_name(value)
+                    .map(|g| g.gid())
+                    .ok_or_else(|| LlaError::Filter(format!("unknown group '{}'", value)))?,
+            }),
+            "perm" => MetadataPredicate::Permissions(parse_permissions(value)?),
+            "type" => MetadataPredicate::Type(match value {
+                "f" | "file" => EntryKind::File,
+                "d" | "dir" | "directory" => EntryKind::Dir,
+                "l" | "link" | "symlink" => EntryKind::Symlink,
+                "other" => EntryKind::Other,
+                _ => {
+                    return Err(LlaError::Filter(format!(
+                        "invalid type '{}': expected file, dir, symlink or other",
+                        value
+                    )))
+                }
+            }),
+            _ => return Err(LlaError::Filter(format!("unknown predicate '{}'", key))),
+        };
+
+        Ok(MetadataFilter { predicate })
+    }
+
+    fn matches(&self, metadata: &EntryMetadata) -> bool {
+        match &self.predicate {
+            MetadataPredicate::Size(comparison, size) => comparison.compare(metadata.size, *size),
+            MetadataPredicate::Time { field, start, end } => {
+                let timestamp = match field {
+                    TimeField::Modified => metadata.modified,
+                    TimeField::Accessed => metadata.accessed,
+                    TimeField::Created => metadata.created,
+                };
+                timestamp >= *start && timestamp < *end
+            }
+            MetadataPredicate::Owner(uid) => metadata.uid == *uid,
+            MetadataPredicate::Group(gid) => metadata.gid == *gid,
+            MetadataPredicate::Permissions(PermissionMatch::Exact(mode)) => {
+                metadata.permissions & 0o7777 == *mode
+            }
+            MetadataPredicate::Permissions(PermissionMatch::Symbolic {
+                shifts,
+                any_class,
+                set,
+                bits,
+            }) => {
+                let mut classes = shifts
+                    .iter()
+                    .map(|shift| (metadata.permissions >> shift) & 0o7);
+                if !*set {
+                    classes.all(|class| class & bits == 0)
+                } else if *any_class {
+                    classes.any(|class| class & bits == *bits)
+                } else {
+                    classes.all(|class| class & bits == *bits)
+                }
+            }
+            MetadataPredicate::Type(kind) => match kind {
+                EntryKind::File => metadata.is_file,
+                EntryKind::Dir => metadata.is_dir,
+                EntryKind::Symlink => metadata.is_symlink,
+                EntryKind::Other => !metadata.is_file && !metadata.is_dir && !metadata.is_symlink,
+            },
+        }
+    }
+}
+
+fn parse_size(value: &str) -> Result<u64> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+
+    let multiplier: u64 = match unit.to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        "t" | "tb" | "tib" => 1024 * 1024 * 1024 * 1024,
+        _ => {
+            return Err(LlaError::Filter(format!(
+                "invalid size unit '{}' in '{}': expected B, K, M, G or T",
+                unit, value
+            )))
+        }
+    };
+
+    number
+        .parse::<f64>()
+        .map(|n| (n * multiplier as f64) as u64)
+        .map_err(|_| LlaError::Filter(format!("invalid size '{}'", value)))
+}
+
+fn parse_time(field: TimeField, value: &str) -> Result<MetadataPredicate> {
+    let (comparison, value) = Comparison::split(value);
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let unit_secs = match unit {
+        "s" => Some(1),
+        "m" => Some(60),
+        "h" => Some(60 * 60),
+        "d" => Some(24 * 60 * 60),
+        "w" => Some(7 * 24 * 60 * 60),
+        "y" => Some(365 * 24 * 60 * 60),
+        _ => None,
+    };
+
+    // Relative ages compare the other way round: `modified:<7d` means "less than
+    // seven days old", i.e. a timestamp after now - 7d.
+    let (comparison, threshold, span) = match (number.parse::<u64>(), unit_secs) {
+        (Ok(amount), Some(unit_secs)) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let age = |units: u64| now.saturating_sub(units.saturating_mul(unit_secs));
+            // `modified:=7d` covers the whole seventh day, like a date does.
+            match comparison.unwrap_or(Comparison::Lt).flipped() {
+                Comparison::Eq => (Comparison::Eq, age(amount.saturating_add(1)), unit_secs),
+                comparison => (comparison, age(amount), 1),
+            }
+        }
+        _ => {
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+                LlaError::Filter(format!(
+                    "invalid time '{}': expected an age like 30m, 12h, 7d, 2w, 1y or a date like 2024-01-31",
+                    value
+                ))
+            })?;
+            let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+            let threshold = Local
+                .from_local_datetime(&midnight)
+                .earliest()
+                .map_or(0, |dt| dt.timestamp().max(0) as u64);
+            (
+                comparison.unwrap_or(Comparison::Eq),
+                threshold,
+                24 * 60 * 60,
+            )
+        }
+    };
+
+    let (start, end) = match comparison {
+        Comparison::Lt => (0, threshold),
+        Comparison::Le => (0, threshold + span),
+        Comparison::Gt => (threshold + span, u64::MAX),
+        Comparison::Ge => (threshold, u64::MAX),
+        Comparison::Eq => (threshold, threshold + span),
+    };
+
+    Ok(MetadataPredicate::Time { field, start, end })
+}
+
+fn parse_permissions(value: &str) -> Result<PermissionMatch> {
+    if !value.is_empty() && value.chars().all(|c| c.is_digit(8)) {
+        return u32::from_str_radix(value, 8)
+            .ok()
+            .filter(|mode| *mode <= 0o7777)
+            .map(PermissionMatch::Exact)
+            .ok_or_else(|| LlaError::Filter(format!("invalid permission mode '{}'", value)));
+    }
+
+    let invalid = || {
+        LlaError::Filter(format!(
+            "invalid permission '{}': expected an octal mode like 644 or a symbolic mode like +x, u+w, go-w",
+            value
+        ))
+    };
+
+    let op_index = value.find(['+', '-']).ok_or_else(invalid)?;
+    let (who, rest) = value.split_at(op_index);
+    let set = rest.starts_with('+');
+
+    let mut shifts = Vec::new();
+    for c in who.chars() {
+        match c {
+            'u' => shifts.push(6),
+            'g' => shifts.push(3),
+            'o' => shifts.push(0),
+            'a' => shifts.extend([6, 3, 0]),
+            _ => return Err(invalid()),
+        }
+    }
+    let any_class = shifts.is_empty();
+    if any_class {
+        shifts = vec![6, 3, 0];
+    }
+
+    let mut bits = 0;
+    for c in rest[1..].chars() {
+        bits |= match c {
+            'r' => 0o4,
+            'w' => 0o2,
+            'x' => 0o1,
+            _ => return Err(invalid()),
+        };
+    }
+    if bits == 0 {
+        return Err(invalid());
+    }
+
+    Ok(PermissionMatch::Symbolic {
+        shifts,
+        any_class,
+        set,
+        bits,
+    })
+}
+
+impl FileFilter for MetadataFilter {
+    fn filter_files(&self, files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        Ok(files
+            .iter()
+            .filter(|file| {
+                file.symlink_metadata()
+                    .map(|m| self.matches(&convert_metadata(&m)))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn filter_entries(&self, entries: &[DecoratedEntry]) -> Result<Vec<DecoratedEntry>> {
+        Ok(entries
+            .iter()
+            .filter(|entry| entry.metadata.as_ref().is_some_and(|m| self.matches(m)))
+            .cloned()
+            .collect())
+    }
+}
@@ -1,20 +1,61 @@
 use crate::error::Result;
+use crate::plugin::PluginManager;
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 pub trait FileFilter: Send + Sync {
     fn filter_files(&self, files: &[PathBuf]) -> Result<Vec<PathBuf>>;
+
+    /// Filters fully built entries. Filters that only look at names can rely on
+    /// the default, which delegates to `filter_files`.
+    fn filter_entries(&self, entries: &[DecoratedEntry]) -> Result<Vec<DecoratedEntry>> {
+        let paths: Vec<PathBuf> = entries.iter().map(|e| PathBuf::from(&e.path)).collect();
+        let kept: HashSet<PathBuf> = self.filter_files(&paths)?.into_iter().collect();
+        Ok(entries
+            .iter()
+            .zip(paths.iter())
+            .filter(|(_, path)| kept.contains(*path))
+            .map(|(entry, _)| entry.clone())
+            .collect())
+    }
+
+    /// Whether the filter needs plugin decorations (`custom_fields`) and must
+    /// therefore run after `PluginManager::decorate_entry`.
+    fn requires_decoration(&self) -> bool {
+        false
+    }
+
+    /// Fetches what the filter needs from plugins before `filter_entries` runs
+    /// on `entries` or a part of them.
+    fn prepare(
+        &self,
+        _entries: &[DecoratedEntry],
+        _plugin_manager: &mut PluginManager,
+    ) -> Result<()> {
+        Ok(())
+    }
 }
 
 mod case_insensitive;
 mod composite;
+mod expression;
 mod extension;
+mod field;
 mod glob_filter;
+mod metadata;
 mod pattern;
+mod plugin;
 mod regex_filter;
 
 pub use case_insensitive::CaseInsensitiveFilter;
 pub use composite::{CompositeFilter, FilterOperation};
+pub use expression::FilterExpr;
 pub use extension::ExtensionFilter;
+pub use field::FieldFilter;
+pub(crate) use field::{leading_number, lookup_field};
 pub use glob_filter::GlobFilter;
+pub use metadata::MetadataFilter;
 pub use pattern::PatternFilter;
+pub use plugin::PluginFilter;
 pub use regex_filter::RegexFilter;
@@ -0,0 +1,67 @@
+use super::FileFilter;
+use crate::error::{LlaError, Result};
+use crate::plugin::PluginManager;
+use lla_plugin_interface::proto::DecoratedEntry;
+use parking_lot::RwLock;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Filters on a predicate provided by a plugin, e.g. `category:Docs`.
+///
+/// The plugin decides on a whole batch of decorated entries in `prepare`, so
+/// like `field:` predicates this filter is evaluated after decoration.
+pub struct PluginFilter {
+    plugin: String,
+    filter: String,
+    argument: String,
+    matches: RwLock<HashSet<String>>,
+}
+
+impl PluginFilter {
+    pub fn new(plugin: String, filter: String, argument: String) -> Self {
+        Self {
+            plugin,
+            filter,
+            argument,
+            matches: RwLock::new(HashSet::new()),
+        }
+    }
+}
+
+impl FileFilter for PluginFilter {
+    fn filter_files(&self, _files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        Err(LlaError::Filter(format!(
+            "{}:{} can only be evaluated on decorated entries",
+            self.filter, self.argument
+        )))
+    }
+
+    fn filter_entries(&self, entries: &[DecoratedEntry]) -> Result<Vec<DecoratedEntry>> {
+        let matches = self.matches.read();
+        Ok(entries
+            .iter()
+            .filter(|entry| matches.contains(&entry.path))
+            .cloned()
+            .collect())
+    }
+
+    fn requires_decoration(&self) -> bool {
+        true
+    }
+
+    fn prepare(
+        &self,
+        entries: &[DecoratedEntry],
+        plugin_manager: &mut PluginManager,
+    ) -> Result<()> {
+        let results =
+            plugin_manager.filter_entries(&self.plugin, &self.filter, &self.argument, entries)?;
+        *self.matches.write() = entries
+            .iter()
+            .zip(results)
+            .filter(|(_, matches)| *matches)
+            .map(|(entry, _)| entry.path.clone())
+            .collect();
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use super::FileFilter;
-use crate::error::Result;
+use crate::error::{LlaError, Result};
 use regex::Regex;
 use std::path::PathBuf;
 
@@ -8,10 +8,10 @@ pub struct RegexFilter {
 }
 
 impl RegexFilter {
-    pub fn new(pattern: String) -> Self {
-        RegexFilter {
-            regex: Regex::new(&pattern).unwrap_or_else(|_| Regex::new(".*").unwrap()),
-        }
+    pub fn new(pattern: String) -> Result<Self> {
+        let regex = Regex::new(&pattern)
+            .map_err(|e| LlaError::Filter(format!("invalid regex pattern '{}': {}", pattern, e)))?;
+        Ok(RegexFilter { regex })
     }
 }
 
@@ -0,0 +1,292 @@
+use super::serializable::GitStatuses;
+use crate::filter::lookup_field;
+use crate::theme::{color_value_to_color, ColorValue};
+use crate::utils::color::*;
+use crate::utils::icons::format_with_icon;
+use crate::utils::size::shown_size;
+use crate::utils::time::{changed_secs, TimeField};
+use colored::*;
+use lla_plugin_interface::proto::DecoratedEntry;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs::{self, Permissions};
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use unicode_width::UnicodeWidthStr;
+use users::{get_group_by_gid, get_user_by_uid};
+
+static USER_CACHE: Lazy<Mutex<HashMap<u32, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static GROUP_CACHE: Lazy<Mutex<HashMap<u32, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub const COLUMN_KEYS: &[&str] = &[
+    "perms", "size", "mtime", "atime", "btime", "ctime", "inode", "links", "owner", "group", "git",
+    "name",
+];
+
+/// A column of the long and table views, selected with `--columns` or
+/// `formatters.long.columns`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Permissions,
+    Size,
+    Modified,
+    Accessed,
+    Created,
+    Changed,
+    Inode,
+    Links,
+    Owner,
+    Group,
+    Git,
+    Field(String),
+    Name,
+}
+
+impl Column {
+    pub fn parse(key: &str) -> Option<Self> {
+        if let Some(field) = key.strip_prefix("field:") {
+            return (!field.is_empty()).then(|| Column::Field(field.to_string()));
+        }
+
+        let column = match key {
+            "perms" | "permissions" => Column::Permissions,
+            "size" => Column::Size,
+            "mtime" | "modified" | "date" => Column::Modified,
+            "atime" | "accessed" => Column::Accessed,
+            "btime" | "created" => Column::Created,
+            "ctime" | "changed" => Column::Changed,
+            "inode" => Column::Inode,
+            "links" | "nlink" => Column::Links,
+            "owner" | "user" => Column::Owner,
+            "group" => Column::Group,
+            "git" => Column::Git,
+            "name" => Column::Name,
+            _ => return None,
+        };
+        Some(column)
+    }
+
+    pub fn header(&self) -> &str {
+        match self {
+            Column::Permissions => "Permissions",
+            Column::Size => "Size",
+            Column::Modified => "Modified",
+            Column::Accessed => "Accessed",
+            Column::Created => "Created",
+            Column::Changed => "Changed",
+            Column::Inode => "Inode",
+            Column::Links => "Links",
+            Column::Owner => "User",
+            Column::Group => "Group",
+            Column::Git => "Git",
+            Column::Field(name) => name,
+            Column::Name => "Name",
+        }
+    }
+
+    pub fn time(field: TimeField) -> Self {
+        match field {
+            TimeField::Modified => Column::Modified,
+            TimeField::Accessed => Column::Accessed,
+            TimeField::Created => Column::Created,
+            TimeField::Changed => Column::Changed,
+        }
+    }
+
+    pub fn align_right(&self) -> bool {
+        matches!(self, Column::Size | Column::Inode | Column::Links)
+    }
+}
+
+/// Parses a list of column keys, e.g. `["perms", "size", "field:tags", "name"]`.
+pub fn parse_columns<S: AsRef<str>>(keys: &[S]) -> std::result::Result<Vec<Column>, String> {
+    keys.iter()
+        .map(|key| key.as_ref().trim())
+        .filter(|key| !key.is_empty())
+        .map(|key| {
+            Column::parse(key).ok_or_else(|| {
+                format!(
+                    "unknown column '{}', expected one of: {} or field:<name>",
+                    key,
+                    COLUMN_KEYS.join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+pub fn requires_decoration(columns: &[Column]) -> bool {
+    columns
+        .iter()
+        .any(|column| matches!(column, Column::Field(_)))
+}
+
+/// Renders the cells of the selected columns for a set of entries.
+pub struct ColumnRenderer<'a> {
+    pub show_icons: bool,
+    pub permission_format: &'a str,
+    pub relative_dates: bool,
+    pub show_symlink_targets: bool,
+    git: Option<GitStatuses>,
+}
+
+impl<'a> ColumnRenderer<'a> {
+    pub fn new(columns: &[Column], show_icons: bool, permission_format: &'a str) -> Self {
+        let git = columns.contains(&Column::Git).then(GitStatuses::new);
+
+        Self {
+            show_icons,
+            permission_format,
+            relative_dates: false,
+            show_symlink_targets: false,
+            git,
+        }
+    }
+
+    pub fn render(&self, column: &Column, entry: &DecoratedEntry) -> String {
+        let metadata = entry.metadata.as_ref().cloned().unwrap_or_default();
+        let path = Path::new(&entry.path);
+
+        match column {
+            Column::Permissions => {
+                let perms = Permissions::from_mode(metadata.permissions);
+                colorize_permissions(&perms, Some(self.permission_format))
+            }
+            Column::Size => colorize_size(shown_size(entry)).to_string(),
+            Column::Modified => self.render_date(metadata.modified),
+            Column::Accessed => self.render_date(metadata.accessed),
+            Column::Created => self.render_date(metadata.created),
+            Column::Changed => self.render_date(changed_secs(&entry.path).unwrap_or(0)),
+            Column::Inode => match fs::symlink_metadata(path) {
+                Ok(m) => m.ino().to_string().bright_black().to_string(),
+                Err(_) => "-".bright_black().to_string(),
+            },
+            Column::Links => match fs::symlink_metadata(path) {
+                Ok(m) => m.nlink().to_string().bright_black().to_string(),
+                Err(_) => "-".bright_black().to_string(),
+            },
+            Column::Owner => colorize_user(&user_name(metadata.uid)).to_string(),
+            Column::Group => colorize_group(&group_name(metadata.gid)).to_string(),
+            Column::Git => {
+                let status = self.git.as_ref().and_then(|git| git.status_or_rollup(path));
+                colorize_git_status(status.as_deref())
+            }
+            Column::Field(name) => match lookup_field(entry, name) {
+                Some(value) if !value.is_empty() => value,
+                _ => "-".bright_black().to_string(),
+            },
+            Column::Name => self.render_name(entry, path, metadata.is_symlink),
+        }
+    }
+
+    fn render_date(&self, secs: u64) -> String {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        if self.relative_dates {
+            colorize_date_relative(&time).to_string()
+        } else {
+            colorize_date(&time).to_string()
+        }
+    }
+
+    fn render_name(&self, entry: &DecoratedEntry, path: &Path, is_symlink: bool) -> String {
+        let colored_name = colorize_file_name(path).to_string();
+        let name = colorize_file_name_with_icon(
+            path,
+            format_with_icon(path, colored_name, self.show_icons),
+        )
+        .to_string();
+
+        if !self.show_symlink_targets || !is_symlink {
+            return name;
+        }
+
+        let broken = entry.custom_fields.contains_key("invalid_symlink");
+        match entry.custom_fields.get("symlink_target") {
+            Some(target) if broken => {
+                format!("{} -> {} (broken)", name, target.red().bold())
+            }
+            Some(target) => format!("{} -> {}", name, colorize_symlink_target(Path::new(target))),
+            None if broken => format!("{} -> {}", name, "(broken link)".red().bold()),
+            None => name,
+        }
+    }
+}
+
+pub fn visible_width(s: &str) -> usize {
+    String::from_utf8_lossy(&strip_ansi_escapes::strip(s).unwrap_or_default()).width()
+}
+
+fn user_name(uid: u32) -> String {
+    let mut cache = USER_CACHE.lock().unwrap();
+    cache
+        .entry(uid)
+        .or_insert_with(|| {
+            get_user_by_uid(uid)
+                .map(|u| u.name().to_string_lossy().into_owned())
+                .unwrap_or_else(|| uid.to_string())
+        })
+        .clone()
+}
+
+fn group_name(gid: u32) -> String {
+    let mut cache = GROUP_CACHE.lock().unwrap();
+    cache
+        .entry(gid)
+        .or_insert_with(|| {
+            get_group_by_gid(gid)
+                .map(|g| g.name().to_string_lossy().into_owned())
+                .unwrap_or_else(|| gid.to_string())
+        })
+        .clone()
+}
+
+/// Adds the git column in front of the name unless it's already shown, as
+/// done by `--git-status`.
+pub fn insert_git_column(columns: &mut Vec<Column>) {
+    if columns.contains(&Column::Git) {
+        return;
+    }
+    let position = columns
+        .iter()
+        .position(|column| *column == Column::Name)
+        .unwrap_or(columns.len());
+    columns.insert(position, Column::Git);
+}
+
+/// Renders a porcelain `XY` status with the theme's git colors: the index
+/// state as staged, the worktree state as modified and `--` for unmodified
+/// entries.
+pub fn colorize_git_status(status: Option<&str>) -> String {
+    let colors = &get_theme().colors;
+    let clean = color_value_to_color(&colors.git_clean);
+    let status = match status {
+        Some(".") | None => return "--".color(clean).to_string(),
+        Some(status) => status,
+    };
+
+    match status {
+        "??" => "??"
+            .color(color_value_to_color(&colors.git_untracked))
+            .to_string(),
+        "!!" => "!!".color(clean).to_string(),
+        _ => {
+            let mut chars = status.chars();
+            let index = chars.next().unwrap_or('.');
+            let worktree = chars.next().unwrap_or('.');
+            let paint = |c: char, color: &ColorValue| {
+                if c == '.' {
+                    "-".color(clean).to_string()
+                } else {
+                    c.to_string().color(color_value_to_color(color)).to_string()
+                }
+            };
+            format!(
+                "{}{}",
+                paint(index, &colors.git_staged),
+                paint(worktree, &colors.git_modified)
+            )
+        }
+    }
+}
@@ -1,13 +1,38 @@
 use crate::error::Result;
-use crate::plugin::PluginManager;
 use lla_plugin_interface::proto::DecoratedEntry;
+use std::path::Path;
 
-use super::serializable::{find_git_root, get_git_status_map, to_serializable};
+use super::fields::{select_fields, value_to_cell, OutputField};
+use super::serializable::{to_serializable, GitStatuses};
+
+/// Columns written without `--fields`. Columns added after the first
+/// release are appended so existing positions stay the same.
+const COLUMNS: &[&str] = &[
+    "path",
+    "name",
+    "extension",
+    "file_type",
+    "size_bytes",
+    "modified",
+    "created",
+    "accessed",
+    "mode_octal",
+    "owner_user",
+    "owner_group",
+    "inode",
+    "hard_links",
+    "symlink_target",
+    "is_hidden",
+    "git_status",
+    "schema_version",
+    "allocated_bytes",
+    "changed",
+];
 
 pub fn write_csv_stream<I>(
     entries: I,
-    _plugin_manager: &mut PluginManager,
     include_git_status: bool,
+    fields: Option<&[OutputField]>,
 ) -> Result<()>
 where
     I: IntoIterator<Item = DecoratedEntry>,
@@ -16,55 +41,33 @@ where
     let handle = stdout.lock();
     let mut wtr = csv::Writer::from_writer(handle);
 
-    wtr.write_record(&[
-        "path",
-        "name",
-        "extension",
-        "file_type",
-        "size_bytes",
-        "modified",
-        "created",
-        "accessed",
-        "mode_octal",
-        "owner_user",
-        "owner_group",
-        "inode",
-        "hard_links",
-        "symlink_target",
-        "is_hidden",
-        "git_status",
-    ])?;
+    match fields {
+        Some(fields) => wtr.write_record(fields.iter().map(|field| field.name.as_str()))?,
+        None => wtr.write_record(COLUMNS)?,
+    }
 
-    let mut git_status_map = None;
-    let mut git_root = None;
+    let git = GitStatuses::new();
 
     for entry in entries {
+        let path = Path::new(&entry.path);
         let git_status = if include_git_status {
-            if git_root.is_none() {
-                if let Some(parent) = std::path::Path::new(&entry.path).parent() {
-                    if let Some(root) = find_git_root(parent) {
-                        git_root = Some(root.clone());
-                        git_status_map = Some(get_git_status_map(&root));
-                    }
-                }
-            }
-            if let Some(root) = &git_root {
-                let full = std::path::Path::new(&entry.path);
-                let rel = full.strip_prefix(root).unwrap_or(full);
-                let rel_str = rel.to_string_lossy().to_string();
-                git_status_map
-                    .as_ref()
-                    .and_then(|m| m.get(&rel_str))
-                    .cloned()
-            } else {
-                None
-            }
+            git.status(path)
         } else {
             None
         };
 
         let serial = to_serializable(&entry, git_status);
 
+        if let Some(fields) = fields {
+            let selected = select_fields(&serial, fields)?;
+            wtr.write_record(
+                fields
+                    .iter()
+                    .map(|field| value_to_cell(&selected[field.name.as_str()])),
+            )?;
+            continue;
+        }
+
         wtr.write_record(&[
             serial.path,
             serial.name,
@@ -82,6 +85,12 @@ where
             serial.symlink_target.unwrap_or_default(),
             serial.is_hidden.to_string(),
             serial.git_status.unwrap_or_default(),
+            serial.schema_version.to_string(),
+            serial
+                .allocated_bytes
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            serial.changed.unwrap_or_default(),
         ])?;
     }
 
@@ -0,0 +1,113 @@
+use crate::error::Result;
+use serde_json::{json, Map, Value};
+
+use super::serializable::{SerializableEntry, FIELD_NAMES};
+
+/// A field selected with `--fields`: an entry field such as `size_bytes` or a
+/// plugin field such as `plugin.tags`, optionally renamed with `alias=field`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputField {
+    pub name: String,
+    source: FieldSource,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FieldSource {
+    Entry(String),
+    Plugin(String),
+}
+
+impl OutputField {
+    pub fn parse(spec: &str) -> std::result::Result<Self, String> {
+        let (alias, field) = match spec.split_once('=') {
+            Some((alias, field)) => (Some(alias.trim()), field.trim()),
+            None => (None, spec.trim()),
+        };
+
+        let source = if let Some(plugin_field) = field.strip_prefix("plugin.") {
+            if plugin_field.is_empty() {
+                return Err("missing plugin field name after 'plugin.'".to_string());
+            }
+            FieldSource::Plugin(plugin_field.to_string())
+        } else if FIELD_NAMES.contains(&field) {
+            FieldSource::Entry(field.to_string())
+        } else {
+            return Err(format!(
+                "unknown field '{}', expected one of: {} or plugin.<name>",
+                field,
+                FIELD_NAMES.join(", ")
+            ));
+        };
+
+        let name = match alias {
+            Some("") => return Err(format!("empty name for field '{}'", field)),
+            Some(alias) => alias.to_string(),
+            None => field.to_string(),
+        };
+        Ok(Self { name, source })
+    }
+
+    /// JSON Schema of this field, looked up in the entry schema's
+    /// `properties` for entry fields.
+    pub fn schema(&self, properties: &Map<String, Value>) -> Value {
+        match &self.source {
+            FieldSource::Entry(field) => properties.get(field).cloned().unwrap_or(json!({})),
+            FieldSource::Plugin(field) => json!({
+                "type": ["string", "null"],
+                "description": format!("Plugin field '{}', null when no plugin set it.", field),
+            }),
+        }
+    }
+
+    fn value(&self, entry: &Map<String, Value>) -> Value {
+        match &self.source {
+            FieldSource::Entry(field) => entry.get(field).cloned(),
+            FieldSource::Plugin(field) => entry
+                .get("plugin")
+                .and_then(|plugin| plugin.get(field))
+                .cloned(),
+        }
+        .unwrap_or(Value::Null)
+    }
+}
+
+/// Parses a comma-separated `--fields` list, e.g. `path,bytes=size_bytes,plugin.tags`.
+pub fn parse_fields(spec: &str) -> std::result::Result<Vec<OutputField>, String> {
+    let fields = spec
+        .split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(OutputField::parse)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    if fields.is_empty() {
+        return Err("no fields given".to_string());
+    }
+    for (i, field) in fields.iter().enumerate() {
+        if fields[..i].iter().any(|other| other.name == field.name) {
+            return Err(format!("duplicate field name '{}'", field.name));
+        }
+    }
+    Ok(fields)
+}
+
+/// The selected fields of `entry` as a JSON object, in the requested order.
+pub fn select_fields(entry: &SerializableEntry, fields: &[OutputField]) -> Result<Value> {
+    let Value::Object(all) = serde_json::to_value(entry)? else {
+        return Ok(Value::Null);
+    };
+    let selected = fields
+        .iter()
+        .map(|field| (field.name.clone(), field.value(&all)))
+        .collect();
+    Ok(Value::Object(selected))
+}
+
+/// Text of a value in a CSV cell: strings unquoted, null empty and nested
+/// values as JSON.
+pub fn value_to_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
@@ -3,6 +3,7 @@ use crate::error::Result;
 use crate::plugin::PluginManager;
 use crate::utils::color::*;
 use crate::utils::icons::format_with_icon;
+use crate::utils::size::shown_size;
 use colored::*;
 use lla_plugin_interface::proto::DecoratedEntry;
 use std::fs::Permissions;
@@ -46,7 +47,7 @@ impl FuzzyFormatter {
 
         let perms = Permissions::from_mode(metadata.permissions);
         let perms_display = colorize_permissions(&perms, Some(&self.permission_format));
-        let size = colorize_size(metadata.size);
+        let size = colorize_size(shown_size(entry));
         let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(metadata.modified);
         let date = colorize_date(&modified);
 
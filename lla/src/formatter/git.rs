@@ -7,9 +7,8 @@ use crate::utils::icons::format_with_icon;
 use colored::*;
 use console::strip_ansi_codes;
 use lla_plugin_interface::proto::DecoratedEntry;
-use std::collections::HashMap;
+use lla_plugin_utils::git::GitRepo;
 use std::path::Path;
-use std::process::Command;
 use unicode_width::UnicodeWidthStr;
 
 pub struct GitFormatter {
@@ -29,49 +28,7 @@ impl GitFormatter {
         theme::color_value_to_color(value)
     }
 }
-#[derive(Debug, Default)]
-struct GitInfo {
-    branch: String,
-    ahead: usize,
-    behind: usize,
-}
-
 impl GitFormatter {
-    fn get_git_info(path: &Path) -> Option<GitInfo> {
-        let status = Command::new("git")
-            .arg("rev-parse")
-            .arg("--is-inside-work-tree")
-            .current_dir(path)
-            .output()
-            .ok()?;
-
-        if !status.status.success() {
-            return None;
-        }
-
-        let mut info = GitInfo::default();
-
-        if let Ok(output) = Command::new("git")
-            .args(["status", "-b", "--porcelain=v2"])
-            .current_dir(path)
-            .output()
-        {
-            for line in String::from_utf8_lossy(&output.stdout).lines() {
-                if line.starts_with("# branch.head ") {
-                    info.branch = line.split_whitespace().nth(2)?.to_string();
-                } else if line.starts_with("# branch.ab ") {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    if parts.len() >= 4 {
-                        info.ahead = parts[2].trim_start_matches('+').parse().unwrap_or(0);
-                        info.behind = parts[3].trim_start_matches('-').parse().unwrap_or(0);
-                    }
-                }
-            }
-        }
-
-        Some(info)
-    }
-
     fn format_git_status(status: &str) -> (String, String) {
         let theme = color::get_theme();
         let staged_color = Self::get_theme_color(&theme.colors.executable);
@@ -110,86 +67,6 @@ impl GitFormatter {
 
         (status_str.to_string(), "".to_string())
     }
-
-    fn get_git_status_map(workspace_root: &Path) -> HashMap<String, String> {
-        let mut status_map = HashMap::new();
-
-        if let Ok(output) = Command::new("git")
-            .args(["status", "--porcelain=v2", "--untracked-files=all"])
-            .current_dir(workspace_root)
-            .output()
-        {
-            for line in String::from_utf8_lossy(&output.stdout).lines() {
-                if line.is_empty() || line.starts_with('#') {
-                    continue;
-                }
-
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.is_empty() {
-                    continue;
-                }
-
-                match parts[0] {
-                    "1" | "2" if parts.len() >= 9 => {
-                        let xy = parts[1];
-                        let path = parts[8];
-                        status_map.insert(path.to_string(), xy.to_string());
-                    }
-                    "?" if parts.len() >= 2 => {
-                        status_map.insert(parts[1].to_string(), "??".to_string());
-                    }
-                    "!" if parts.len() >= 2 => {
-                        status_map.insert(parts[1].to_string(), "!!".to_string());
-                    }
-                    _ => {}
-                }
-            }
-        }
-
-        if let Ok(output) = Command::new("git")
-            .args(["ls-files"])
-            .current_dir(workspace_root)
-            .output()
-        {
-            for line in String::from_utf8_lossy(&output.stdout).lines() {
-                status_map
-                    .entry(line.to_string())
-                    .or_insert_with(|| ".".to_string());
-            }
-        }
-
-        status_map
-    }
-
-    fn get_last_commit_info(path: &Path, file_path: &Path) -> Option<(String, String, String)> {
-        let output = Command::new("git")
-            .args([
-                "log",
-                "-1",
-                "--format=%h|%s|%cr|%an",
-                "--",
-                file_path.to_str()?,
-            ])
-            .current_dir(path)
-            .output()
-            .ok()?;
-
-        if !output.status.success() {
-            return None;
-        }
-
-        let log = String::from_utf8_lossy(&output.stdout);
-        let parts: Vec<&str> = log.trim().split('|').collect();
-        if parts.len() >= 4 {
-            Some((
-                parts[0].to_string(),
-                parts[2].to_string(),
-                parts[3].to_string(),
-            ))
-        } else {
-            None
-        }
-    }
 }
 
 impl FileFormatter for GitFormatter {
@@ -212,17 +89,21 @@ impl FileFormatter for GitFormatter {
         let time_color = Self::get_theme_color(&ColorValue::Named("bright black".to_string()));
         let author_color = Self::get_theme_color(&theme.colors.user);
 
-        let workspace_root = Path::new(&files[0].path)
-            .ancestors()
-            .find(|p| p.join(".git").exists())
-            .unwrap_or(Path::new("."));
-
-        let git_info = match GitFormatter::get_git_info(workspace_root) {
-            Some(info) => info,
-            None => return Ok("Not a git repository".red().to_string()),
+        let Some(repo) = GitRepo::discover(Path::new(&files[0].path)) else {
+            return Ok("Not a git repository".red().to_string());
+        };
+        let git_info = repo.branch().unwrap_or_default();
+        let paths: Vec<&Path> = files.iter().map(|file| Path::new(&file.path)).collect();
+        let last_commits = repo.last_commits(&paths);
+        let commit_info = |path: &Path| match last_commits.get(path) {
+            Some(commit) => (
+                commit.short_id.clone(),
+                commit.relative_time(),
+                commit.author.clone(),
+            ),
+            None => ("-".to_string(), "never".to_string(), "-".to_string()),
         };
 
-        let status_map = GitFormatter::get_git_status_map(workspace_root);
         let mut max_name_width: usize = 0;
         let mut max_hash_width: usize = 0;
         let mut max_time_width: usize = 0;
@@ -235,8 +116,7 @@ impl FileFormatter for GitFormatter {
                 path,
                 format_with_icon(path, name.to_string(), self.show_icons),
             );
-            let commit_info = GitFormatter::get_last_commit_info(workspace_root, path)
-                .unwrap_or_else(|| ("-".to_string(), "never".to_string(), "-".to_string()));
+            let commit_info = commit_info(path);
 
             max_name_width = max_name_width.max(Self::strip_ansi(&name_with_icon).width());
             max_hash_width = max_hash_width.max(commit_info.0.len());
@@ -247,7 +127,7 @@ impl FileFormatter for GitFormatter {
         let mut output = format!(
             "\n{} {}{}{}\n{}\n",
             "⎇".color(branch_color),
-            git_info.branch.color(branch_color).bold(),
+            git_info.name.color(branch_color).bold(),
             if git_info.ahead > 0 {
                 format!(" ↑{}", git_info.ahead).color(ahead_color)
             } else {
@@ -285,16 +165,12 @@ impl FileFormatter for GitFormatter {
                 format_with_icon(path, name.to_string(), self.show_icons),
             );
 
-            let relative_path = path.strip_prefix(workspace_root).unwrap_or(path);
-            let relative_path_str = relative_path.to_string_lossy();
-
-            let (status_str, _) = status_map
-                .get(relative_path_str.as_ref())
-                .map(|s| GitFormatter::format_git_status(s))
+            let (status_str, _) = repo
+                .status(path)
+                .map(GitFormatter::format_git_status)
                 .unwrap_or_else(|| ("".to_string(), "".to_string()));
 
-            let commit_info = GitFormatter::get_last_commit_info(workspace_root, relative_path)
-                .unwrap_or_else(|| ("-".to_string(), "never".to_string(), "-".to_string()));
+            let commit_info = commit_info(path);
 
             let plugin_fields = plugin_manager.format_fields(file, "git").join(" ");
 
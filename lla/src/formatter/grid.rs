@@ -1,3 +1,5 @@
+use super::columns::colorize_git_status;
+use super::serializable::GitStatuses;
 use super::FileFormatter;
 use crate::plugin::PluginManager;
 use crate::utils::color::colorize_file_name;
@@ -12,6 +14,7 @@ pub struct GridFormatter {
     pub show_icons: bool,
     pub grid_ignore: bool,
     pub max_width: usize,
+    pub git_status: bool,
 }
 
 impl GridFormatter {
@@ -20,8 +23,15 @@ impl GridFormatter {
             show_icons,
             grid_ignore,
             max_width,
+            git_status: false,
         }
     }
+
+    /// Prefixes every name with its two-character git status.
+    pub fn with_git_status(mut self, git_status: bool) -> Self {
+        self.git_status = git_status;
+        self
+    }
 }
 impl FileFormatter for GridFormatter {
     fn format_files(
@@ -42,17 +52,27 @@ impl FileFormatter for GridFormatter {
                 .unwrap_or(80)
         };
 
+        let git = self.git_status.then(GitStatuses::new);
+
         let mut formatted_entries = Vec::with_capacity(files.len());
         let mut max_width = 0;
 
         for file in files {
             let path = Path::new(&file.path);
             let colored_name = colorize_file_name(path).to_string();
-            let name_with_icon = colorize_file_name_with_icon(
+            let mut name_with_icon = colorize_file_name_with_icon(
                 path,
                 format_with_icon(path, colored_name, self.show_icons),
             )
             .to_string();
+            if self.git_status {
+                let status = git.as_ref().and_then(|git| git.status_or_rollup(path));
+                name_with_icon = format!(
+                    "{} {}",
+                    colorize_git_status(status.as_deref()),
+                    name_with_icon
+                );
+            }
             let plugin_fields = plugin_manager.format_fields(file, "grid").join(" ");
             let total_str = if plugin_fields.is_empty() {
                 name_with_icon.clone()
@@ -0,0 +1,269 @@
+use crate::error::Result;
+use crate::theme::{color_to_css, color_value_to_css, get_file_color};
+use crate::utils::color::get_theme;
+use crate::utils::size::format_size;
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::Path;
+
+use super::serializable::{
+    collect_serializable, mode_to_symbolic, plugin_value_text, SerializableEntry,
+};
+
+const STYLE: &str = r#"
+body { background: #1e1e1e; color: #d4d4d4; font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; font-size: 13px; margin: 2em; }
+h1 { font-size: 16px; font-weight: normal; }
+h1 .path { color: var(--directory); font-weight: bold; }
+.summary { color: #7f7f7f; }
+table { border-collapse: collapse; }
+th { text-align: left; color: #7f7f7f; font-weight: normal; border-bottom: 1px solid #3c3c3c; padding: 4px 12px; cursor: pointer; user-select: none; white-space: nowrap; }
+th[data-order="asc"]::after { content: " ▲"; }
+th[data-order="desc"]::after { content: " ▼"; }
+td { padding: 2px 12px; white-space: nowrap; }
+tr:hover td { background: #2a2d2e; }
+td.num { text-align: right; }
+.file { color: var(--file); }
+.dir { color: var(--directory); font-weight: bold; }
+.symlink { color: var(--symlink); font-style: italic; }
+.exec { color: var(--executable); font-weight: bold; }
+.target { color: #7f7f7f; }
+.size { color: var(--size); }
+.date { color: var(--date); }
+.user { color: var(--user); }
+.group { color: var(--group); }
+.p-d { color: var(--perm-dir); }
+.p-r { color: var(--perm-read); }
+.p-w { color: var(--perm-write); }
+.p-x { color: var(--perm-exec); }
+.p-n { color: var(--perm-none); }
+.git-staged { color: var(--git-staged); }
+.git-modified { color: var(--git-modified); }
+.git-untracked { color: var(--git-untracked); }
+.git-clean { color: var(--git-clean); }
+"#;
+
+const SCRIPT: &str = r#"
+document.querySelectorAll("th").forEach(function (th, column) {
+  th.addEventListener("click", function () {
+    var tbody = th.closest("table").tBodies[0];
+    var order = th.dataset.order === "asc" ? "desc" : "asc";
+    th.parentNode.querySelectorAll("th").forEach(function (other) { delete other.dataset.order; });
+    th.dataset.order = order;
+    var numeric = th.dataset.type === "number";
+    var key = function (row) {
+      var cell = row.cells[column];
+      var value = cell.dataset.sort !== undefined ? cell.dataset.sort : cell.textContent;
+      return numeric ? parseFloat(value) || 0 : value.toLowerCase();
+    };
+    var rows = Array.prototype.slice.call(tbody.rows);
+    rows.sort(function (a, b) {
+      var x = key(a), y = key(b);
+      var result = x < y ? -1 : x > y ? 1 : 0;
+      return order === "asc" ? result : -result;
+    });
+    rows.forEach(function (row) { tbody.appendChild(row); });
+  });
+});
+"#;
+
+/// Writes the listing as a self-contained HTML page: colors come from the
+/// active theme and clicking a column header sorts by it.
+pub fn write_html<I>(entries: I, include_git_status: bool, title: &str) -> Result<()>
+where
+    I: IntoIterator<Item = DecoratedEntry>,
+{
+    let (rows, plugin_keys) = collect_serializable(entries, include_git_status);
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(html, "<title>lla: {}</title>", escape(title));
+    html.push_str("<style>");
+    html.push_str(&theme_variables());
+    html.push_str(STYLE);
+    html.push_str("</style>\n</head>\n<body>\n");
+    let _ = writeln!(
+        html,
+        "<h1>lla <span class=\"path\">{}</span></h1>",
+        escape(title)
+    );
+    let _ = writeln!(html, "<p class=\"summary\">{} entries</p>", rows.len());
+
+    html.push_str("<table>\n<thead>\n<tr>");
+    let mut headers = vec![
+        ("Name", "text"),
+        ("Type", "text"),
+        ("Size", "number"),
+        ("Modified", "text"),
+        ("Permissions", "text"),
+        ("Owner", "text"),
+        ("Group", "text"),
+    ];
+    if include_git_status {
+        headers.push(("Git", "text"));
+    }
+    for (label, kind) in headers {
+        let _ = write!(html, "<th data-type=\"{}\">{}</th>", kind, label);
+    }
+    for key in &plugin_keys {
+        let _ = write!(html, "<th data-type=\"text\">{}</th>", escape(key));
+    }
+    html.push_str("</tr>\n</thead>\n<tbody>\n");
+
+    for row in &rows {
+        html.push_str("<tr>");
+        html.push_str(&name_cell(row));
+        let _ = write!(html, "<td>{}</td>", escape(&row.file_type));
+        let _ = write!(
+            html,
+            "<td class=\"num size\" data-sort=\"{}\">{}</td>",
+            row.size_bytes,
+            escape(&format_size(row.size_bytes))
+        );
+        let _ = write!(html, "<td class=\"date\">{}</td>", escape(&row.modified));
+        html.push_str(&permissions_cell(row));
+        let _ = write!(
+            html,
+            "<td class=\"user\">{}</td><td class=\"group\">{}</td>",
+            escape(row.owner_user.as_deref().unwrap_or("-")),
+            escape(row.owner_group.as_deref().unwrap_or("-"))
+        );
+        if include_git_status {
+            html.push_str(&git_cell(row.git_status.as_deref()));
+        }
+        for key in &plugin_keys {
+            let _ = write!(
+                html,
+                "<td>{}</td>",
+                escape(&plugin_value_text(row.plugin.get(key)))
+            );
+        }
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</tbody>\n</table>\n<script>");
+    html.push_str(SCRIPT);
+    html.push_str("</script>\n</body>\n</html>\n");
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(html.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn theme_variables() -> String {
+    let colors = &get_theme().colors;
+    let variables = [
+        ("file", &colors.file),
+        ("directory", &colors.directory),
+        ("symlink", &colors.symlink),
+        ("executable", &colors.executable),
+        ("size", &colors.size),
+        ("date", &colors.date),
+        ("user", &colors.user),
+        ("group", &colors.group),
+        ("perm-dir", &colors.permission_dir),
+        ("perm-read", &colors.permission_read),
+        ("perm-write", &colors.permission_write),
+        ("perm-exec", &colors.permission_exec),
+        ("perm-none", &colors.permission_none),
+        ("git-staged", &colors.git_staged),
+        ("git-modified", &colors.git_modified),
+        ("git-untracked", &colors.git_untracked),
+        ("git-clean", &colors.git_clean),
+    ];
+
+    let mut css = String::from("\n:root {");
+    for (name, value) in variables {
+        let _ = write!(css, " --{}: {};", name, color_value_to_css(value));
+    }
+    css.push_str(" }");
+    css
+}
+
+fn name_cell(row: &SerializableEntry) -> String {
+    let mode = u32::from_str_radix(&row.mode_octal, 8).unwrap_or(0);
+    let (class, style) = match row.file_type.as_str() {
+        "dir" => ("dir", String::new()),
+        "symlink" => ("symlink", String::new()),
+        _ if mode & 0o111 != 0 => ("exec", String::new()),
+        _ => match get_file_color(Path::new(&row.path)) {
+            Some(color) => ("file", format!(" style=\"color: {}\"", color_to_css(color))),
+            None => ("file", String::new()),
+        },
+    };
+
+    let target = row
+        .symlink_target
+        .as_deref()
+        .map(|target| format!(" <span class=\"target\">→ {}</span>", escape(target)))
+        .unwrap_or_default();
+    format!(
+        "<td data-sort=\"{}\"><span class=\"{}\"{}>{}</span>{}</td>",
+        escape(&row.path),
+        class,
+        style,
+        escape(&row.path),
+        target
+    )
+}
+
+fn permissions_cell(row: &SerializableEntry) -> String {
+    let mode = u32::from_str_radix(&row.mode_octal, 8).unwrap_or(0);
+    let symbolic = mode_to_symbolic(&row.file_type, mode);
+    let mut cell = format!("<td data-sort=\"{}\">", row.mode_octal);
+    for (i, c) in symbolic.chars().enumerate() {
+        let class = match c {
+            'd' | 'l' if i == 0 => "p-d",
+            'r' => "p-r",
+            'w' => "p-w",
+            'x' => "p-x",
+            _ => "p-n",
+        };
+        let _ = write!(cell, "<span class=\"{}\">{}</span>", class, c);
+    }
+    cell.push_str("</td>");
+    cell
+}
+
+/// Porcelain `XY` status in the theme's git colors, like
+/// `colorize_git_status`: the index state as staged and the worktree state as
+/// modified.
+fn git_cell(status: Option<&str>) -> String {
+    let status = match status {
+        Some(".") | None => return "<td class=\"git-clean\">--</td>".to_string(),
+        Some("??") => return "<td class=\"git-untracked\">??</td>".to_string(),
+        Some("!!") => return "<td class=\"git-clean\">!!</td>".to_string(),
+        Some(status) => status,
+    };
+    let mut chars = status.chars();
+    let paint = |c: Option<char>, class: &str| match c {
+        Some('.') | None => "<span class=\"git-clean\">-</span>".to_string(),
+        Some(c) => format!(
+            "<span class=\"{}\">{}</span>",
+            class,
+            escape(&c.to_string())
+        ),
+    };
+    format!(
+        "<td data-sort=\"{}\">{}{}</td>",
+        escape(status),
+        paint(chars.next(), "git-staged"),
+        paint(chars.next(), "git-modified")
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
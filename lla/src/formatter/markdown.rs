@@ -0,0 +1,93 @@
+use crate::error::Result;
+use crate::utils::size::format_size;
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::io::{self, Write};
+
+use super::serializable::{
+    collect_serializable, mode_to_symbolic, plugin_value_text, SerializableEntry,
+};
+
+/// Writes the listing as a GitHub-flavored Markdown table, with one column per
+/// plugin field found in the listing.
+pub fn write_markdown<I>(entries: I, include_git_status: bool) -> Result<()>
+where
+    I: IntoIterator<Item = DecoratedEntry>,
+{
+    let (rows, plugin_keys) = collect_serializable(entries, include_git_status);
+    let mut stdout = io::BufWriter::new(io::stdout());
+
+    let mut header = vec![
+        "Name",
+        "Type",
+        "Size",
+        "Modified",
+        "Permissions",
+        "Owner",
+        "Group",
+    ];
+    let mut align = vec![":--", ":--", "--:", ":--", ":--", ":--", ":--"];
+    if include_git_status {
+        header.push("Git");
+        align.push(":-:");
+    }
+    for key in &plugin_keys {
+        header.push(key);
+        align.push(":--");
+    }
+
+    let header: Vec<String> = header.into_iter().map(escape_cell).collect();
+    writeln!(stdout, "| {} |", header.join(" | "))?;
+    writeln!(stdout, "| {} |", align.join(" | "))?;
+
+    for row in &rows {
+        let mut cells = vec![
+            name_cell(row),
+            row.file_type.clone(),
+            format_size(row.size_bytes),
+            escape_cell(&row.modified),
+            code(&mode_to_symbolic(&row.file_type, mode_bits(row))),
+            escape_cell(row.owner_user.as_deref().unwrap_or("-")),
+            escape_cell(row.owner_group.as_deref().unwrap_or("-")),
+        ];
+        if include_git_status {
+            cells.push(match row.git_status.as_deref() {
+                Some(status) if status != "." => code(status),
+                _ => String::new(),
+            });
+        }
+        for key in &plugin_keys {
+            cells.push(escape_cell(&plugin_value_text(row.plugin.get(key))));
+        }
+        writeln!(stdout, "| {} |", cells.join(" | "))?;
+    }
+
+    stdout.flush()?;
+    Ok(())
+}
+
+fn name_cell(row: &SerializableEntry) -> String {
+    match &row.symlink_target {
+        Some(target) => format!("{} → {}", code(&row.path), code(target)),
+        None => code(&row.path),
+    }
+}
+
+fn mode_bits(row: &SerializableEntry) -> u32 {
+    u32::from_str_radix(&row.mode_octal, 8).unwrap_or(0)
+}
+
+/// Wraps text in a code span so names like `__init__.py` aren't rendered as
+/// emphasis, using a longer fence when the text contains backticks.
+fn code(text: &str) -> String {
+    let text = escape_cell(text);
+    if text.contains('`') {
+        format!("`` {} ``", text)
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+/// Pipes end a cell even inside code spans, and a row must stay on one line.
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
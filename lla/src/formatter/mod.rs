@@ -11,17 +11,24 @@ pub trait FileFormatter {
     ) -> Result<String>;
 }
 
+pub mod columns;
 pub mod csv;
 mod default;
+pub mod fields;
 mod fuzzy;
 mod git;
 mod grid;
+pub mod html;
 pub mod json;
 mod long;
+pub mod markdown;
+mod plugin;
 mod recursive;
+pub mod schema;
 pub mod serializable;
 mod sizemap;
 mod table;
+pub mod template;
 mod timeline;
 mod tree;
 
@@ -30,6 +37,7 @@ pub use fuzzy::FuzzyFormatter;
 pub use git::GitFormatter;
 pub use grid::GridFormatter;
 pub use long::LongFormatter;
+pub use plugin::PluginFormatter;
 pub use recursive::RecursiveFormatter;
 pub use sizemap::SizeMapFormatter;
 pub use table::TableFormatter;
@@ -0,0 +1,42 @@
+use super::FileFormatter;
+use crate::error::Result;
+use crate::plugin::PluginManager;
+use lla_plugin_interface::proto::DecoratedEntry;
+
+/// A view rendered by a plugin, selected with `--format <plugin>:<formatter>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginFormatter {
+    pub plugin: String,
+    pub formatter: String,
+}
+
+impl PluginFormatter {
+    /// Parses `<plugin>:<formatter>`. Values containing placeholders are
+    /// `--format` templates, so this only accepts plain names.
+    pub fn parse(value: &str) -> Option<Self> {
+        if value.contains('{') {
+            return None;
+        }
+        let (plugin, formatter) = value.split_once(':')?;
+        let valid = |name: &str| !name.is_empty() && !name.contains(char::is_whitespace);
+        if !valid(plugin) || !valid(formatter) {
+            return None;
+        }
+        Some(Self {
+            plugin: plugin.to_string(),
+            formatter: formatter.to_string(),
+        })
+    }
+}
+
+impl FileFormatter for PluginFormatter {
+    fn format_files(
+        &self,
+        files: &[DecoratedEntry],
+        plugin_manager: &mut PluginManager,
+        _depth: Option<usize>,
+    ) -> Result<String> {
+        let output = plugin_manager.format_entries(&self.plugin, &self.formatter, files)?;
+        Ok(output.trim_end_matches('\n').to_string())
+    }
+}
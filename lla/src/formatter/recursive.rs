@@ -4,26 +4,32 @@ use crate::plugin::PluginManager;
 use crate::theme::{self, ColorValue};
 use crate::utils::color::{self, colorize_file_name, colorize_file_name_with_icon};
 use crate::utils::icons::format_with_icon;
+use crate::utils::time::{get_time_style, TimeField};
 use chrono::{DateTime, Local};
 use colored::*;
 use lla_plugin_interface::proto::DecoratedEntry;
 use std::collections::BTreeMap;
 use std::path::Path;
-use std::time::{Duration, UNIX_EPOCH};
+use std::time::SystemTime;
 
 pub struct RecursiveFormatter {
     pub show_icons: bool,
+    pub time_field: TimeField,
 }
 
 impl RecursiveFormatter {
-    pub fn new(show_icons: bool) -> Self {
-        Self { show_icons }
+    pub fn new(show_icons: bool, time_field: TimeField) -> Self {
+        Self {
+            show_icons,
+            time_field,
+        }
     }
 
-    fn format_date(timestamp: u64) -> ColoredString {
-        let datetime = UNIX_EPOCH + Duration::from_secs(timestamp);
-        let datetime: DateTime<Local> = DateTime::from(datetime);
-        let date_str = datetime.format("%Y-%m-%d %H:%M").to_string();
+    fn format_date(time: SystemTime) -> ColoredString {
+        let date_str = get_time_style().format(time).unwrap_or_else(|| {
+            let datetime: DateTime<Local> = DateTime::from(time);
+            datetime.format("%Y-%m-%d %H:%M").to_string()
+        });
         let color = theme::color_value_to_color(&ColorValue::Named("bright black".to_string()));
         date_str.color(color)
     }
@@ -38,8 +44,8 @@ impl RecursiveFormatter {
     }
 
     fn print_entry(&self, entry: &DecoratedEntry, plugin_manager: &mut PluginManager) {
-        if let Some(metadata) = &entry.metadata {
-            let date = Self::format_date(metadata.modified);
+        if entry.metadata.is_some() {
+            let date = Self::format_date(self.time_field.time(entry));
             let path = Path::new(&entry.path);
             let colored_name = colorize_file_name(path).to_string();
             let name = colorize_file_name_with_icon(
@@ -57,6 +63,11 @@ impl RecursiveFormatter {
         }
     }
 
+    fn print_header(parent: &str) {
+        println!("\n{}", parent.color(Self::get_header_color()).bold());
+        println!("{}", "─".repeat(40).color(Self::get_separator_color()));
+    }
+
     fn get_parent_path(path: &str) -> String {
         Path::new(path)
             .parent()
@@ -86,10 +97,10 @@ impl FileFormatter for RecursiveFormatter {
 
         let single_group = groups.len() == 1;
 
-        for (parent, entries) in &groups {
+        for (parent, entries) in &mut groups {
+            entries.sort_by(|a, b| a.path.cmp(&b.path));
             if !(single_group && parent == ".") {
-                println!("\n{}", parent.color(Self::get_header_color()).bold());
-                println!("{}", "─".repeat(40).color(Self::get_separator_color()));
+                Self::print_header(parent);
             }
 
             for entry in entries {
@@ -0,0 +1,95 @@
+use serde_json::{json, Value};
+
+use super::fields::OutputField;
+use super::serializable::{FIELD_NAMES, SCHEMA_VERSION};
+
+/// JSON Schema of one entry of `--json`/`--ndjson` output, printed by
+/// `lla schema`. Keep in sync with [`super::serializable::SerializableEntry`].
+pub fn entry_schema() -> Value {
+    let nullable = |kind: &str| json!({ "type": [kind, "null"] });
+    let timestamp = |description: &str| {
+        json!({
+            "type": "string",
+            "format": "date-time",
+            "description": format!("{} RFC 3339 in UTC.", description),
+        })
+    };
+    let optional_timestamp = |description: &str| {
+        let mut schema = timestamp(description);
+        schema["type"] = json!(["string", "null"]);
+        schema
+    };
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "lla entry",
+        "description": "One file system entry as emitted by lla --json (array items) and --ndjson (one per line).",
+        "type": "object",
+        "required": FIELD_NAMES,
+        "properties": {
+            "schema_version": {
+                "const": SCHEMA_VERSION,
+                "description": "Version of this schema. New fields may be added without a bump; renamed, removed or retyped fields bump it."
+            },
+            "path": { "type": "string", "description": "Path as listed, relative to the working directory unless an absolute path was given." },
+            "name": { "type": "string", "description": "Final path component." },
+            "extension": nullable("string"),
+            "file_type": { "enum": ["file", "dir", "symlink", "other"] },
+            "size_bytes": {
+                "type": "integer",
+                "minimum": 0,
+                "description": "Apparent size, also with --disk-usage. Directories hold their total size with --include-dirs."
+            },
+            "allocated_bytes": {
+                "type": ["integer", "null"],
+                "minimum": 0,
+                "description": "Space allocated on disk. Directories hold the space allocated for everything below them with --include-dirs."
+            },
+            "modified": timestamp("Last modification time."),
+            "created": optional_timestamp("Creation time, null where the file system doesn't record it."),
+            "accessed": optional_timestamp("Last access time."),
+            "changed": optional_timestamp("Last inode change time (ctime)."),
+            "mode_octal": {
+                "type": "string",
+                "pattern": "^[0-7]{4}$",
+                "description": "Permission bits including setuid, setgid and sticky, e.g. \"0644\"."
+            },
+            "owner_user": nullable("string"),
+            "owner_group": nullable("string"),
+            "inode": { "type": ["integer", "null"], "minimum": 0 },
+            "hard_links": { "type": ["integer", "null"], "minimum": 0 },
+            "symlink_target": nullable("string"),
+            "is_hidden": { "type": "boolean" },
+            "git_status": {
+                "type": ["string", "null"],
+                "description": "Two-character git porcelain status such as \"M.\", \".M\", \"??\" or \"!!\"; \".\" for clean files. Only set with -G/--git."
+            },
+            "plugin": {
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+                "description": "Fields provided by enabled plugins, keyed by field name."
+            }
+        }
+    })
+}
+
+/// JSON Schema of one entry of `--json`/`--ndjson` output with `--fields`,
+/// printed by `lla schema --fields`. Every selected field is present, null
+/// when the entry has no value for it.
+pub fn fields_schema(fields: &[OutputField]) -> Value {
+    let mut schema = entry_schema();
+    let properties = schema["properties"]
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+    let selected: serde_json::Map<String, Value> = fields
+        .iter()
+        .map(|field| (field.name.clone(), field.schema(&properties)))
+        .collect();
+    let names: Vec<&String> = selected.keys().collect();
+    schema["required"] = json!(names);
+    schema["description"] =
+        json!("One file system entry as emitted by lla --json and --ndjson with these --fields.");
+    schema["properties"] = Value::Object(selected);
+    schema
+}
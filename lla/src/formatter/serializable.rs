@@ -1,9 +1,13 @@
 use lla_plugin_interface::proto::DecoratedEntry;
+use lla_plugin_utils::git::GitRepoCache;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
+use crate::utils::size::{allocated_size, allocated_total, ALLOCATED_SIZE_FIELD};
+use crate::utils::time::changed_secs;
 use chrono::{SecondsFormat, TimeZone, Utc};
 use once_cell::sync::Lazy;
 use std::os::unix::fs::MetadataExt;
@@ -13,16 +17,23 @@ use users::{get_group_by_gid, get_user_by_uid};
 static USER_CACHE: Lazy<Mutex<HashMap<u32, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 static GROUP_CACHE: Lazy<Mutex<HashMap<u32, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
+/// Version of the machine output format. Adding fields keeps the version;
+/// renaming, removing or changing the type of a field bumps it.
+pub const SCHEMA_VERSION: u32 = 1;
+
 #[derive(Debug, Serialize)]
 pub struct SerializableEntry {
+    pub schema_version: u32,
     pub path: String,
     pub name: String,
     pub extension: Option<String>,
     pub file_type: String,
     pub size_bytes: u64,
+    pub allocated_bytes: Option<u64>,
     pub modified: String,
     pub created: Option<String>,
     pub accessed: Option<String>,
+    pub changed: Option<String>,
     pub mode_octal: String,
     pub owner_user: Option<String>,
     pub owner_group: Option<String>,
@@ -34,6 +45,30 @@ pub struct SerializableEntry {
     pub plugin: HashMap<String, serde_json::Value>,
 }
 
+/// Field names of [`SerializableEntry`] in output order.
+pub const FIELD_NAMES: &[&str] = &[
+    "schema_version",
+    "path",
+    "name",
+    "extension",
+    "file_type",
+    "size_bytes",
+    "allocated_bytes",
+    "modified",
+    "created",
+    "accessed",
+    "changed",
+    "mode_octal",
+    "owner_user",
+    "owner_group",
+    "inode",
+    "hard_links",
+    "symlink_target",
+    "is_hidden",
+    "git_status",
+    "plugin",
+];
+
 fn fmt_ts_opt(secs: u64) -> Option<String> {
     if secs == 0 {
         return None;
@@ -52,6 +87,24 @@ fn mode_to_octal(permissions: u32) -> String {
     format!("{:04o}", permissions & 0o7777)
 }
 
+/// `ls`-style permission string such as `drwxr-xr-x`.
+pub fn mode_to_symbolic(file_type: &str, permissions: u32) -> String {
+    let kind = match file_type {
+        "dir" => 'd',
+        "symlink" => 'l',
+        _ => '-',
+    };
+    let mut out = String::with_capacity(10);
+    out.push(kind);
+    for shift in [6, 3, 0] {
+        let bits = (permissions >> shift) & 0o7;
+        out.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        out.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        out.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    out
+}
+
 fn uid_to_name(uid: u32) -> Option<String> {
     if uid == 0 && get_user_by_uid(uid).is_none() {
         return None;
@@ -108,9 +161,13 @@ pub fn to_serializable(entry: &DecoratedEntry, git_status: Option<String>) -> Se
     .to_string();
 
     // Extra FS data
-    let (inode, hard_links) = match fs::symlink_metadata(&entry.path) {
-        Ok(m) => (Some(m.ino()), Some(m.nlink() as u64)),
-        Err(_) => (None, None),
+    let (inode, hard_links, allocated_bytes) = match fs::symlink_metadata(&entry.path) {
+        Ok(m) => (
+            Some(m.ino()),
+            Some(m.nlink()),
+            Some(allocated_total(entry).unwrap_or_else(|| allocated_size(&m))),
+        ),
+        Err(_) => (None, None, None),
     };
 
     let symlink_target = if md.is_symlink {
@@ -132,18 +189,24 @@ pub fn to_serializable(entry: &DecoratedEntry, git_status: Option<String>) -> Se
 
     let mut plugin: HashMap<String, serde_json::Value> = HashMap::new();
     for (k, v) in &entry.custom_fields {
+        if k == ALLOCATED_SIZE_FIELD {
+            continue;
+        }
         plugin.insert(k.clone(), serde_json::Value::String(v.clone()));
     }
 
     SerializableEntry {
+        schema_version: SCHEMA_VERSION,
         path: entry.path.clone(),
         name,
         extension,
         file_type,
         size_bytes: md.size,
+        allocated_bytes,
         modified: fmt_ts_required(md.modified),
         created: fmt_ts_opt(md.created),
         accessed: fmt_ts_opt(md.accessed),
+        changed: changed_secs(&entry.path).and_then(fmt_ts_opt),
         mode_octal: mode_to_octal(md.permissions),
         owner_user,
         owner_group,
@@ -156,66 +219,77 @@ pub fn to_serializable(entry: &DecoratedEntry, git_status: Option<String>) -> Se
     }
 }
 
-pub fn find_git_root(start: &Path) -> Option<PathBuf> {
-    let mut dir = start;
-    loop {
-        if dir.join(".git").exists() {
-            return Some(dir.to_path_buf());
-        }
-        match dir.parent() {
-            Some(parent) => dir = parent,
-            None => return None,
-        }
+/// Git status of the working trees a listing lives in, read in process and
+/// looked up by canonical path so relative listing paths resolve correctly.
+/// Each repository is found from the parent directory of an entry and read
+/// once, so a listed submodule doesn't stand in for the repository being
+/// listed and recursive listings crossing into other repositories see theirs.
+#[derive(Default)]
+pub struct GitStatuses(RefCell<GitRepoCache>);
+
+impl GitStatuses {
+    pub fn new() -> Self {
+        Self::default()
     }
-}
 
-pub fn get_git_status_map(workspace_root: &Path) -> HashMap<String, String> {
-    use std::process::Command;
-    let mut status_map = HashMap::new();
-
-    if let Ok(output) = Command::new("git")
-        .args(["status", "--porcelain=v2", "--untracked-files=all"])
-        .current_dir(workspace_root)
-        .output()
-    {
-        for line in String::from_utf8_lossy(&output.stdout).lines() {
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
-
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.is_empty() {
-                continue;
-            }
-
-            match parts[0] {
-                "1" | "2" if parts.len() >= 9 => {
-                    let xy = parts[1];
-                    let path = parts[8];
-                    status_map.insert(path.to_string(), xy.to_string());
-                }
-                "?" if parts.len() >= 2 => {
-                    status_map.insert(parts[1].to_string(), "??".to_string());
-                }
-                "!" if parts.len() >= 2 => {
-                    status_map.insert(parts[1].to_string(), "!!".to_string());
-                }
-                _ => {}
-            }
-        }
+    pub fn status(&self, path: &Path) -> Option<String> {
+        let repo = self.0.borrow_mut().repo(path)?;
+        repo.status(path).map(str::to_string)
     }
 
-    if let Ok(output) = std::process::Command::new("git")
-        .args(["ls-files"])
-        .current_dir(workspace_root)
-        .output()
-    {
-        for line in String::from_utf8_lossy(&output.stdout).lines() {
-            status_map
-                .entry(line.to_string())
-                .or_insert_with(|| ".".to_string());
-        }
+    /// The status of a file, or for a directory the rollup of the changes
+    /// below it.
+    pub fn status_or_rollup(&self, path: &Path) -> Option<String> {
+        let repo = self.0.borrow_mut().repo(path)?;
+        repo.status(path)
+            .or_else(|| repo.dir_status(path))
+            .map(str::to_string)
+    }
+}
+
+/// Custom fields lla sets itself; they aren't shown as plugin columns.
+const INTERNAL_FIELDS: &[&str] = &["symlink_target", "invalid_symlink", ALLOCATED_SIZE_FIELD];
+
+/// Converts a whole listing for the report writers, which need every row up
+/// front to know which plugin field columns to lay out. Returns the entries
+/// and the sorted plugin field names.
+pub fn collect_serializable<I>(
+    entries: I,
+    include_git_status: bool,
+) -> (Vec<SerializableEntry>, Vec<String>)
+where
+    I: IntoIterator<Item = DecoratedEntry>,
+{
+    let git = GitStatuses::new();
+    let mut plugin_keys = BTreeSet::new();
+    let mut rows = Vec::new();
+
+    for entry in entries {
+        let path = Path::new(&entry.path);
+        let git_status = if include_git_status {
+            git.status(path)
+        } else {
+            None
+        };
+
+        plugin_keys.extend(
+            entry
+                .custom_fields
+                .keys()
+                .filter(|key| !INTERNAL_FIELDS.contains(&key.as_str()))
+                .cloned(),
+        );
+        rows.push(to_serializable(&entry, git_status));
     }
 
-    status_map
+    (rows, plugin_keys.into_iter().collect())
+}
+
+/// Text of a plugin field value as shown in reports.
+pub fn plugin_value_text(value: Option<&serde_json::Value>) -> String {
+    match value {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(serde_json::Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
 }
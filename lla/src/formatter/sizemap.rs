@@ -4,6 +4,7 @@ use crate::plugin::PluginManager;
 use crate::theme::{self, ColorValue};
 use crate::utils::color::{self, colorize_file_name, colorize_file_name_with_icon};
 use crate::utils::icons::format_with_icon;
+use crate::utils::size::{format_size, shown_size};
 use colored::*;
 use lla_plugin_interface::proto::DecoratedEntry;
 use std::path::Path;
@@ -45,7 +46,7 @@ impl SizeMapFormatter {
         let size_width = files
             .iter()
             .map(|f| {
-                let size = f.metadata.as_ref().map_or(0, |m| m.size);
+                let size = shown_size(f);
                 Self::visible_width(&format_size(size))
             })
             .max()
@@ -60,7 +61,7 @@ impl SizeMapFormatter {
         (name_width, size_width, bar_width)
     }
 
-    fn create_bar(percentage: f64, width: usize, is_dir: bool) -> String {
+    pub fn create_bar(percentage: f64, width: usize, is_dir: bool) -> String {
         let theme = color::get_theme();
         let percent_width = 6;
         let bar_width = width.saturating_sub(percent_width);
@@ -187,23 +188,20 @@ impl FileFormatter for SizeMapFormatter {
 
         let (name_width, size_width, bar_width) = Self::calculate_layout(files, term_width);
 
-        let total_size: u64 = files
-            .iter()
-            .map(|f| f.metadata.as_ref().map_or(0, |m| m.size))
-            .sum();
+        let total_size: u64 = files.iter().map(shown_size).sum();
 
         let mut output = String::new();
         output.push('\n');
 
         let mut files = files.to_vec();
-        files.sort_by_key(|f| std::cmp::Reverse(f.metadata.as_ref().map_or(0, |m| m.size)));
+        files.sort_by_key(|f| std::cmp::Reverse(shown_size(f)));
 
         for file in &files {
             let path = Path::new(&file.path);
             let colored_name = colorize_file_name(path).to_string();
             let name = format_with_icon(path, colored_name, self.show_icons);
             let metadata = file.metadata.as_ref().unwrap();
-            let size = metadata.size;
+            let size = shown_size(file);
             let size_str = format_size(size);
             let percentage = if total_size > 0 {
                 (size as f64 / total_size as f64) * 100.0
@@ -234,34 +232,3 @@ impl FileFormatter for SizeMapFormatter {
         Ok(output)
     }
 }
-
-fn format_size(size: u64) -> String {
-    const UNITS: [&str; 6] = ["B", "KB", "MB", "GB", "TB", "PB"];
-    if size == 0 {
-        return "0 B".to_string();
-    }
-
-    let size = size as f64;
-    let base = 1024_f64;
-
-    let exp = if size > 0.0 {
-        (size.ln() / base.ln()).floor() as i32
-    } else {
-        0
-    };
-
-    let exp = if exp < 0 {
-        0
-    } else if exp >= UNITS.len() as i32 {
-        (UNITS.len() - 1) as i32
-    } else {
-        exp
-    };
-
-    let size = size / base.powi(exp);
-    if exp == 0 {
-        format!("{:.0} {}", size, UNITS[exp as usize])
-    } else {
-        format!("{:.1} {}", size, UNITS[exp as usize])
-    }
-}
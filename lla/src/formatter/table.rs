@@ -1,28 +1,38 @@
+use super::columns::{Column, ColumnRenderer};
 use super::FileFormatter;
 use crate::error::Result;
 use crate::plugin::PluginManager;
 use crate::theme::{self, ColorValue};
-use crate::utils::color::{self, *};
-use crate::utils::icons::format_with_icon;
+use crate::utils::color;
+use crate::utils::time::TimeField;
 use colored::*;
-use lla_plugin_interface::proto::DecoratedEntry;
 use std::cmp;
-use std::fs::Permissions;
-use std::os::unix::fs::PermissionsExt;
-use std::path::Path;
-use std::time::{Duration, UNIX_EPOCH};
 use unicode_width::UnicodeWidthStr;
 
 pub struct TableFormatter {
     pub show_icons: bool,
     pub permission_format: String,
+    pub columns: Vec<Column>,
 }
 
 impl TableFormatter {
-    pub fn new(show_icons: bool, permission_format: String) -> Self {
+    pub fn new(
+        show_icons: bool,
+        permission_format: String,
+        time_field: TimeField,
+        columns: Option<Vec<Column>>,
+    ) -> Self {
         Self {
             show_icons,
             permission_format,
+            columns: columns.unwrap_or_else(|| {
+                vec![
+                    Column::Permissions,
+                    Column::Size,
+                    Column::time(time_field),
+                    Column::Name,
+                ]
+            }),
         }
     }
 }
@@ -37,35 +47,16 @@ impl TableFormatter {
         Self::strip_ansi(s).width()
     }
 
-    fn calculate_column_widths(files: &[DecoratedEntry], permission_format: &str) -> [usize; 4] {
-        let mut widths = [
-            "Permissions".len(),
-            "Size".len(),
-            "Modified".len(),
-            "Name".len(),
-        ];
-
-        for entry in files {
-            let metadata = entry.metadata.as_ref().cloned().unwrap_or_default();
-            let perms = Permissions::from_mode(metadata.permissions);
-            let perms = colorize_permissions(&perms, Some(permission_format));
-            widths[0] = cmp::max(widths[0], Self::visible_width(&perms));
-
-            let size: ColoredString = colorize_size(metadata.size);
-            widths[1] = cmp::max(widths[1], Self::visible_width(&size));
-
-            let modified = UNIX_EPOCH + Duration::from_secs(metadata.modified);
-            let date = colorize_date(&modified);
-            widths[2] = cmp::max(widths[2], Self::visible_width(&date));
-
-            let path = Path::new(&entry.path);
-            let colored_name = colorize_file_name(path).to_string();
-            let name_with_icon =
-                colorize_file_name_with_icon(path, format_with_icon(path, colored_name, true));
-            widths[3] = cmp::max(widths[3], Self::visible_width(&name_with_icon));
-        }
-
-        widths
+    fn calculate_column_widths(columns: &[Column], rows: &[Vec<String>]) -> Vec<usize> {
+        columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                rows.iter()
+                    .map(|row| Self::visible_width(&row[i]))
+                    .fold(column.header().width(), cmp::max)
+            })
+            .collect()
     }
 
     fn get_border_color() -> Color {
@@ -91,14 +82,14 @@ impl TableFormatter {
         separator.color(border_color).to_string()
     }
 
-    fn create_header(widths: &[usize]) -> String {
+    fn create_header(columns: &[Column], widths: &[usize]) -> String {
         let border_color = Self::get_border_color();
         let header_color = Self::get_header_color();
-        let headers = ["Permissions", "Size", "Modified", "Name"];
         let mut header = String::new();
         header.push('│');
 
-        for (&width, &title) in widths.iter().zip(headers.iter()) {
+        for (&width, column) in widths.iter().zip(columns) {
+            let title = column.header();
             header.push(' ');
             header.push_str(
                 &format!("{:width$}", title, width = width)
@@ -163,27 +154,27 @@ impl FileFormatter for TableFormatter {
             return Ok(String::new());
         }
 
-        let widths = Self::calculate_column_widths(files, &self.permission_format);
+        let renderer = ColumnRenderer::new(&self.columns, self.show_icons, &self.permission_format);
+        let rows: Vec<Vec<String>> = files
+            .iter()
+            .map(|entry| {
+                self.columns
+                    .iter()
+                    .map(|column| renderer.render(column, entry))
+                    .collect()
+            })
+            .collect();
+        let widths = Self::calculate_column_widths(&self.columns, &rows);
 
         let mut output = String::new();
         output.push_str(&Self::create_top_border(&widths));
         output.push('\n');
-        output.push_str(&Self::create_header(&widths));
+        output.push_str(&Self::create_header(&self.columns, &widths));
         output.push('\n');
         output.push_str(&Self::create_separator(&widths));
         output.push('\n');
 
-        for entry in files {
-            let metadata = entry.metadata.as_ref().cloned().unwrap_or_default();
-            let perms = Permissions::from_mode(metadata.permissions);
-            let perms = colorize_permissions(&perms, Some(&self.permission_format));
-            let size = colorize_size(metadata.size);
-            let modified = UNIX_EPOCH + Duration::from_secs(metadata.modified);
-            let date = colorize_date(&modified);
-            let path = Path::new(&entry.path);
-            let colored_name = colorize_file_name(path).to_string();
-            let name = format_with_icon(path, colored_name, self.show_icons);
-
+        for (entry, row) in files.iter().zip(rows) {
             let plugin_fields = plugin_manager.format_fields(entry, "table").join(" ");
             let plugin_suffix = if plugin_fields.is_empty() {
                 String::new()
@@ -191,20 +182,18 @@ impl FileFormatter for TableFormatter {
                 format!(" {}", plugin_fields)
             };
 
-            let formatted_perms = Self::format_cell(&perms, widths[0], false);
-            let formatted_size = Self::format_cell(&size, widths[1], true);
-            let formatted_date = Self::format_cell(&date, widths[2], false);
-            let formatted_name = Self::format_cell(&name, widths[3], false);
-
-            output.push_str(&format!(
-                "│{pad}{}{pad}│{pad}{}{pad}│{pad}{}{pad}│{pad}{}{pad}│{}\n",
-                formatted_perms,
-                formatted_size,
-                formatted_date,
-                formatted_name,
-                plugin_suffix,
-                pad = " ",
-            ));
+            output.push('│');
+            for (i, cell) in row.iter().enumerate() {
+                output.push(' ');
+                output.push_str(&Self::format_cell(
+                    cell,
+                    widths[i],
+                    self.columns[i].align_right(),
+                ));
+                output.push_str(" │");
+            }
+            output.push_str(&plugin_suffix);
+            output.push('\n');
         }
 
         output.push_str(&Self::create_bottom_border(&widths));
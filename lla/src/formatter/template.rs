@@ -0,0 +1,262 @@
+use crate::error::Result;
+use crate::utils::size::format_size;
+use lla_plugin_interface::proto::DecoratedEntry;
+use serde_json::Value;
+use std::io::{self, Write};
+use std::path::Path;
+
+use super::fields::value_to_cell;
+use super::serializable::{
+    mode_to_symbolic, to_serializable, GitStatuses, SerializableEntry, FIELD_NAMES,
+};
+
+/// Placeholders that aren't entry fields of machine output.
+const EXTRA_PLACEHOLDERS: &[&str] = &["size", "size_human", "perms"];
+
+/// A `--format` template such as `{name}\t{size}\t{plugin.git_status}`.
+///
+/// Placeholders are the entry fields of `--json` output, `plugin.<name>` for
+/// plugin fields, `size` (bytes), `size_human` and `perms` (symbolic mode).
+/// `{{` and `}}` are literal braces, and `\t`, `\n`, `\0` and `\\` are
+/// unescaped so templates can be written in single quotes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Field(String),
+    Plugin(String),
+}
+
+impl Template {
+    pub fn parse(template: &str) -> std::result::Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('t') => literal.push('\t'),
+                    Some('n') => literal.push('\n'),
+                    Some('0') => literal.push('\0'),
+                    Some('\\') => literal.push('\\'),
+                    Some(other) => {
+                        literal.push('\\');
+                        literal.push(other);
+                    }
+                    None => literal.push('\\'),
+                },
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("unclosed placeholder '{{{}'", name)),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Self::placeholder(name.trim())?);
+                }
+                '}' => return Err("unmatched '}', use '}}' for a literal brace".to_string()),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    fn placeholder(name: &str) -> std::result::Result<Part, String> {
+        if let Some(field) = name.strip_prefix("plugin.") {
+            if field.is_empty() {
+                return Err("missing plugin field name after 'plugin.'".to_string());
+            }
+            return Ok(Part::Plugin(field.to_string()));
+        }
+        if FIELD_NAMES.contains(&name) || EXTRA_PLACEHOLDERS.contains(&name) {
+            return Ok(Part::Field(name.to_string()));
+        }
+        Err(format!(
+            "unknown placeholder '{{{}}}', expected one of: {}, {} or plugin.<name>",
+            name,
+            FIELD_NAMES.join(", "),
+            EXTRA_PLACEHOLDERS.join(", ")
+        ))
+    }
+
+    /// Template printing only the path, used by `--print0` on its own.
+    pub fn path_only() -> Self {
+        Self {
+            parts: vec![Part::Field("path".to_string())],
+        }
+    }
+
+    pub fn uses_plugin_fields(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|part| matches!(part, Part::Plugin(_)))
+    }
+
+    pub fn uses_git_status(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|part| matches!(part, Part::Field(name) if name == "git_status"))
+    }
+
+    fn render(&self, entry: &SerializableEntry, out: &mut String) -> Result<()> {
+        let needs_value = self.parts.iter().any(|part| match part {
+            Part::Field(name) => !EXTRA_PLACEHOLDERS.contains(&name.as_str()),
+            _ => false,
+        });
+        let value = if needs_value {
+            serde_json::to_value(entry)?
+        } else {
+            Value::Null
+        };
+
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Plugin(field) => out.push_str(
+                    &entry
+                        .plugin
+                        .get(field)
+                        .map(value_to_cell)
+                        .unwrap_or_default(),
+                ),
+                Part::Field(name) => match name.as_str() {
+                    "size" => out.push_str(&entry.size_bytes.to_string()),
+                    "size_human" => out.push_str(&format_size(entry.size_bytes)),
+                    "perms" => {
+                        let mode = u32::from_str_radix(&entry.mode_octal, 8).unwrap_or(0);
+                        out.push_str(&mode_to_symbolic(&entry.file_type, mode));
+                    }
+                    name => out.push_str(&value_to_cell(&value[name])),
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes one line per entry rendered with `template`, ending each entry with
+/// a NUL byte instead of a newline when `print0` is set.
+pub fn write_template<I>(
+    entries: I,
+    template: &Template,
+    print0: bool,
+    include_git_status: bool,
+) -> Result<()>
+where
+    I: IntoIterator<Item = DecoratedEntry>,
+{
+    let mut stdout = io::BufWriter::new(io::stdout());
+    let include_git_status = include_git_status || template.uses_git_status();
+    let git = GitStatuses::new();
+    let mut line = String::new();
+
+    for entry in entries {
+        let path = Path::new(&entry.path);
+        let git_status = if include_git_status {
+            git.status(path)
+        } else {
+            None
+        };
+
+        line.clear();
+        template.render(&to_serializable(&entry, git_status), &mut line)?;
+        line.push(if print0 { '\0' } else { '\n' });
+        stdout.write_all(line.as_bytes())?;
+    }
+
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(text: &str) -> Part {
+        Part::Literal(text.to_string())
+    }
+
+    fn field(name: &str) -> Part {
+        Part::Field(name.to_string())
+    }
+
+    #[test]
+    fn parses_fields_plugin_fields_and_literals() {
+        let template = Template::parse("{name}: { size_human } {plugin.tags}!").unwrap();
+        assert_eq!(
+            template.parts,
+            vec![
+                field("name"),
+                literal(": "),
+                field("size_human"),
+                literal(" "),
+                Part::Plugin("tags".to_string()),
+                literal("!"),
+            ]
+        );
+        assert!(template.uses_plugin_fields());
+        assert!(!template.uses_git_status());
+    }
+
+    #[test]
+    fn unescapes_sequences_and_doubled_braces() {
+        let template = Template::parse(r"{{{path}}}\t\n\0\\\x").unwrap();
+        assert_eq!(
+            template.parts,
+            vec![literal("{"), field("path"), literal("}\t\n\0\\\\x")]
+        );
+    }
+
+    #[test]
+    fn reports_unclosed_placeholders() {
+        assert_eq!(
+            Template::parse("{name} {size").unwrap_err(),
+            "unclosed placeholder '{size'"
+        );
+        assert_eq!(
+            Template::parse("{").unwrap_err(),
+            "unclosed placeholder '{'"
+        );
+    }
+
+    #[test]
+    fn reports_unmatched_closing_braces() {
+        assert_eq!(
+            Template::parse("name}").unwrap_err(),
+            "unmatched '}', use '}}' for a literal brace"
+        );
+    }
+
+    #[test]
+    fn reports_unknown_placeholders() {
+        let error = Template::parse("{nmae}").unwrap_err();
+        assert!(error.starts_with("unknown placeholder '{nmae}', expected one of: "));
+        assert!(error.ends_with(", size, size_human, perms or plugin.<name>"));
+        assert_eq!(
+            Template::parse("{plugin.}").unwrap_err(),
+            "missing plugin field name after 'plugin.'"
+        );
+    }
+}
@@ -4,20 +4,24 @@ use crate::plugin::PluginManager;
 use crate::theme::{self, ColorValue};
 use crate::utils::color::{self, colorize_file_name, colorize_file_name_with_icon};
 use crate::utils::icons::format_with_icon;
+use crate::utils::time::{get_time_style, TimeField};
 use chrono::{DateTime, Duration, Local};
 use colored::*;
 use lla_plugin_interface::proto::DecoratedEntry;
 use std::collections::BTreeMap;
 use std::path::Path;
-use std::time::UNIX_EPOCH;
 
 pub struct TimelineFormatter {
     pub show_icons: bool,
+    pub time_field: TimeField,
 }
 
 impl TimelineFormatter {
-    pub fn new(show_icons: bool) -> Self {
-        Self { show_icons }
+    pub fn new(show_icons: bool, time_field: TimeField) -> Self {
+        Self {
+            show_icons,
+            time_field,
+        }
     }
 
     fn format_relative_time(dt: DateTime<Local>) -> String {
@@ -118,9 +122,7 @@ impl FileFormatter for TimelineFormatter {
         let mut groups: BTreeMap<TimeGroup, Vec<&DecoratedEntry>> = BTreeMap::new();
 
         for file in files {
-            let modified = file.metadata.as_ref().map_or(0, |m| m.modified);
-            let modified = UNIX_EPOCH + std::time::Duration::from_secs(modified);
-            let dt = DateTime::<Local>::from(modified);
+            let dt = DateTime::<Local>::from(self.time_field.time(file));
             let group = TimeGroup::from_datetime(dt);
             groups.entry(group).or_default().push(file);
         }
@@ -135,11 +137,11 @@ impl FileFormatter for TimelineFormatter {
             ));
 
             for entry in entries {
-                let modified = entry.metadata.as_ref().map_or(0, |m| m.modified);
-                let modified = UNIX_EPOCH + std::time::Duration::from_secs(modified);
-                let dt = DateTime::<Local>::from(modified);
-
-                let time_str = Self::format_relative_time(dt).color(Self::get_time_color());
+                let time = self.time_field.time(entry);
+                let time_str = get_time_style()
+                    .format(time)
+                    .unwrap_or_else(|| Self::format_relative_time(DateTime::<Local>::from(time)))
+                    .color(Self::get_time_color());
 
                 let path = Path::new(&entry.path);
                 let colored_name = colorize_file_name(path).to_string();
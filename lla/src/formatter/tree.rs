@@ -1,3 +1,4 @@
+use super::columns::{visible_width, Column, ColumnRenderer};
 use super::FileFormatter;
 use crate::error::Result;
 use crate::plugin::PluginManager;
@@ -13,11 +14,38 @@ const BUFFER_SIZE: usize = 16384;
 
 pub struct TreeFormatter {
     pub show_icons: bool,
+    pub columns: Option<Vec<Column>>,
+    pub permission_format: String,
+    pub relative_dates: bool,
 }
 
 impl TreeFormatter {
     pub fn new(show_icons: bool) -> Self {
-        Self { show_icons }
+        Self {
+            show_icons,
+            columns: None,
+            permission_format: String::new(),
+            relative_dates: false,
+        }
+    }
+
+    /// Shows the given metadata columns to the right of the tree, as used by
+    /// `--tree --long`. The name is always part of the tree itself.
+    pub fn with_columns(
+        mut self,
+        columns: Vec<Column>,
+        permission_format: String,
+        relative_dates: bool,
+    ) -> Self {
+        self.columns = Some(
+            columns
+                .into_iter()
+                .filter(|column| *column != Column::Name)
+                .collect(),
+        );
+        self.permission_format = permission_format;
+        self.relative_dates = relative_dates;
+        self
     }
 
     fn format_entry(&self, path: &Path) -> String {
@@ -111,11 +139,129 @@ impl TreeFormatter {
     }
 }
 
+impl TreeFormatter {
+    /// Collects the tree lines below `path`. `levels_left` is the number of
+    /// levels still shown below this one, or `None` for no limit.
+    fn collect_lines(
+        &self,
+        path: &Path,
+        prefix: &str,
+        is_last: bool,
+        tree: &HashMap<PathBuf, Vec<PathBuf>>,
+        lines: &mut Vec<(String, PathBuf)>,
+        levels_left: Option<usize>,
+    ) {
+        let node_prefix = if is_last { "└── " } else { "├── " };
+        let child_prefix = if is_last { "    " } else { "│   " };
+        lines.push((
+            format!(
+                "{}{}{}",
+                prefix.bright_black(),
+                node_prefix.bright_black(),
+                self.format_entry(path)
+            ),
+            path.to_path_buf(),
+        ));
+
+        if levels_left == Some(0) {
+            return;
+        }
+        if let Some(children) = tree.get(path) {
+            let new_prefix = format!("{}{}", prefix, child_prefix);
+            let last_idx = children.len().saturating_sub(1);
+            for (i, child) in children.iter().enumerate() {
+                self.collect_lines(
+                    child,
+                    &new_prefix,
+                    i == last_idx,
+                    tree,
+                    lines,
+                    levels_left.map(|levels| levels - 1),
+                );
+            }
+        }
+    }
+
+    fn format_long(
+        &self,
+        columns: &[Column],
+        files: &[DecoratedEntry],
+        plugin_manager: &mut PluginManager,
+        depth: Option<usize>,
+    ) -> String {
+        let (root_paths, tree) = self.build_tree(files);
+        let entries: HashMap<PathBuf, &DecoratedEntry> = files
+            .iter()
+            .map(|entry| (PathBuf::from(&entry.path), entry))
+            .collect();
+
+        let mut lines = Vec::with_capacity(files.len());
+        let last_idx = root_paths.len().saturating_sub(1);
+        for (i, path) in root_paths.iter().enumerate() {
+            self.collect_lines(path, "", i == last_idx, &tree, &mut lines, depth);
+        }
+
+        let mut renderer = ColumnRenderer::new(columns, self.show_icons, &self.permission_format);
+        renderer.relative_dates = self.relative_dates;
+
+        let rows: Vec<Vec<String>> = lines
+            .iter()
+            .map(|(_, path)| {
+                columns
+                    .iter()
+                    .map(|column| renderer.render(column, entries[path]))
+                    .collect()
+            })
+            .collect();
+
+        let tree_width = lines
+            .iter()
+            .map(|(line, _)| visible_width(line))
+            .max()
+            .unwrap_or(0);
+        let widths: Vec<usize> = (0..columns.len())
+            .map(|i| {
+                rows.iter()
+                    .map(|row| visible_width(&row[i]))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut output = String::new();
+        for ((line, path), row) in lines.iter().zip(rows) {
+            output.push_str(line);
+            output.push_str(&" ".repeat(tree_width - visible_width(line)));
+            for (i, cell) in row.iter().enumerate() {
+                let padding = " ".repeat(widths[i] - visible_width(cell));
+                output.push_str("  ");
+                if columns[i].align_right() {
+                    output.push_str(&padding);
+                    output.push_str(cell);
+                } else {
+                    output.push_str(cell);
+                    output.push_str(&padding);
+                }
+            }
+
+            let plugin_fields = plugin_manager
+                .format_fields(entries[path], "long")
+                .join(" ");
+            if !plugin_fields.is_empty() {
+                output.push(' ');
+                output.push_str(&plugin_fields);
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
 impl FileFormatter for TreeFormatter {
     fn format_files(
         &self,
         files: &[DecoratedEntry],
-        _plugin_manager: &mut PluginManager,
+        plugin_manager: &mut PluginManager,
         depth: Option<usize>,
     ) -> Result<String> {
         if files.is_empty() {
@@ -126,6 +272,10 @@ impl FileFormatter for TreeFormatter {
             return Ok(String::new());
         }
 
+        if let Some(columns) = &self.columns {
+            return Ok(self.format_long(columns, files, plugin_manager, depth));
+        }
+
         let (root_paths, tree) = self.build_tree(files);
         let mut buffer = Vec::with_capacity(BUFFER_SIZE);
 
@@ -1,9 +1,17 @@
-use super::FileLister;
+use super::{gitignore_walker, FileLister};
 use crate::error::Result;
 use std::fs;
 use std::path::PathBuf;
 
-pub struct BasicLister;
+pub struct BasicLister {
+    respect_gitignore: bool,
+}
+
+impl BasicLister {
+    pub fn new(respect_gitignore: bool) -> Self {
+        Self { respect_gitignore }
+    }
+}
 
 impl FileLister for BasicLister {
     fn list_files(
@@ -15,8 +23,21 @@ impl FileLister for BasicLister {
         let mut files = Vec::with_capacity(16);
 
         let entries = fs::read_dir(directory)?;
-        for entry in entries.flatten() {
-            files.push(entry.path());
+        if !self.respect_gitignore {
+            for entry in entries.flatten() {
+                files.push(entry.path());
+            }
+            return Ok(files);
+        }
+
+        for entry in gitignore_walker(directory)
+            .max_depth(Some(1))
+            .build()
+            .flatten()
+        {
+            if entry.depth() > 0 {
+                files.push(entry.into_path());
+            }
         }
 
         Ok(files)
@@ -1,6 +1,7 @@
 use super::FileLister;
 use crate::utils::color::*;
 use crate::utils::icons::format_with_icon;
+use crate::utils::size::entry_size;
 use crate::{error::Result, theme::color_value_to_color};
 use colored::*;
 use crossbeam_channel::bounded;
@@ -240,13 +241,15 @@ impl SearchIndex {
 pub struct FuzzyLister {
     index: SearchIndex,
     config: crate::config::Config,
+    respect_gitignore: bool,
 }
 
 impl FuzzyLister {
-    pub fn new(config: crate::config::Config) -> Self {
+    pub fn new(config: crate::config::Config, respect_gitignore: bool) -> Self {
         Self {
             index: SearchIndex::new(config.clone()),
             config,
+            respect_gitignore,
         }
     }
 
@@ -277,12 +280,15 @@ impl FuzzyLister {
         let total_indexed_clone = Arc::clone(&total_indexed);
         let indexing_complete_clone = Arc::clone(&indexing_complete);
         let directory = directory.to_string();
+        let respect_gitignore = self.respect_gitignore;
 
         thread::spawn(move || {
             let walker = WalkBuilder::new(&directory)
                 .hidden(false)
-                .git_ignore(false)
-                .ignore(false)
+                .git_ignore(respect_gitignore)
+                .git_global(respect_gitignore)
+                .git_exclude(respect_gitignore)
+                .ignore(respect_gitignore)
                 .follow_links(false)
                 .same_file_system(false)
                 .threads(num_cpus::get())
@@ -668,7 +674,7 @@ impl ResultList {
                 let path = &result.entry.path;
                 let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
                 let metadata = path.metadata().ok();
-                let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+                let size = metadata.as_ref().map(entry_size).unwrap_or(0);
                 let modified = metadata
                     .as_ref()
                     .and_then(|m| m.modified().ok())
@@ -1,5 +1,6 @@
 use crate::error::Result;
-use std::path::PathBuf;
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
 
 pub trait FileLister {
     fn list_files(
@@ -8,13 +9,43 @@ pub trait FileLister {
         recursive: bool,
         depth: Option<usize>,
     ) -> Result<Vec<PathBuf>>;
+
+    /// Lists files in batches so callers can start processing entries while
+    /// the rest of the tree is still being walked.
+    fn list_batches(
+        &self,
+        directory: &str,
+        recursive: bool,
+        depth: Option<usize>,
+    ) -> Result<Box<dyn Iterator<Item = Vec<PathBuf>> + Send>> {
+        let files = self.list_files(directory, recursive, depth)?;
+        Ok(Box::new(std::iter::once(files)))
+    }
 }
 
 pub mod archive;
 mod basic;
 mod fuzzy;
 mod recursive;
+mod walker;
 
 pub use basic::BasicLister;
 pub use fuzzy::FuzzyLister;
 pub use recursive::RecursiveLister;
+
+/// Builds a walker over `directory` honouring `.gitignore`, `.ignore`, the global git excludes
+/// file and `.git/info/exclude`. Hidden files are still yielded so the dotfile
+/// filters keep working, but the `.git` directory itself is skipped.
+pub(crate) fn gitignore_walker<P: AsRef<Path>>(directory: P) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(directory);
+    builder
+        .hidden(false)
+        .parents(true)
+        .ignore(true)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .follow_links(false)
+        .filter_entry(|entry| entry.depth() == 0 || entry.file_name() != ".git");
+    builder
+}
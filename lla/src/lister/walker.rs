@@ -0,0 +1,263 @@
+use super::gitignore_walker;
+use crossbeam_channel::{bounded, Receiver, Sender};
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+const CHANNEL_CAPACITY: usize = 64;
+const BATCH_SIZE: usize = 256;
+
+/// Walks a directory tree with `ignore`'s parallel walker and sends the
+/// entries in batches as they are found.
+///
+/// Each walker thread fills its own batches, so entries arrive in no
+/// particular order. The channel is bounded, so a slow consumer pauses the
+/// walk instead of the whole tree piling up in memory.
+pub struct ParallelWalker {
+    root: PathBuf,
+    max_depth: Option<usize>,
+    max_entries: usize,
+    respect_gitignore: bool,
+}
+
+/// The batch a walker thread is filling; whatever is left is sent when the
+/// thread finishes.
+struct Batch {
+    sender: Sender<Vec<PathBuf>>,
+    paths: Vec<PathBuf>,
+}
+
+impl Batch {
+    fn push(&mut self, path: PathBuf) -> WalkState {
+        self.paths.push(path);
+        if self.paths.len() < BATCH_SIZE {
+            return WalkState::Continue;
+        }
+        match self.sender.send(std::mem::take(&mut self.paths)) {
+            Ok(()) => WalkState::Continue,
+            Err(_) => WalkState::Quit,
+        }
+    }
+}
+
+impl Drop for Batch {
+    fn drop(&mut self) {
+        if !self.paths.is_empty() {
+            let _ = self.sender.send(std::mem::take(&mut self.paths));
+        }
+    }
+}
+
+impl ParallelWalker {
+    pub fn new(
+        root: PathBuf,
+        max_depth: Option<usize>,
+        max_entries: usize,
+        respect_gitignore: bool,
+    ) -> Self {
+        Self {
+            root,
+            max_depth,
+            max_entries,
+            respect_gitignore,
+        }
+    }
+
+    pub fn spawn(self) -> Receiver<Vec<PathBuf>> {
+        let (sender, receiver) = bounded(CHANNEL_CAPACITY);
+
+        let mut builder = if self.respect_gitignore {
+            gitignore_walker(&self.root)
+        } else {
+            let mut builder = WalkBuilder::new(&self.root);
+            builder.standard_filters(false);
+            builder
+        };
+        let walker = builder
+            .max_depth(self.max_depth)
+            .same_file_system(true)
+            .threads(num_cpus::get())
+            .build_parallel();
+        let max_entries = self.max_entries;
+
+        thread::spawn(move || {
+            let counter = AtomicUsize::new(0);
+            walker.run(|| {
+                let mut batch = Batch {
+                    sender: sender.clone(),
+                    paths: Vec::new(),
+                };
+                let counter = &counter;
+                Box::new(move |entry| match entry {
+                    Ok(entry) => visit(entry, &mut batch, counter, max_entries),
+                    Err(_) => WalkState::Continue,
+                })
+            });
+        });
+
+        receiver
+    }
+}
+
+fn is_hidden(name: &std::ffi::OsStr) -> bool {
+    name.to_str().is_some_and(|s| s.starts_with('.'))
+}
+
+fn visit(
+    entry: DirEntry,
+    batch: &mut Batch,
+    counter: &AtomicUsize,
+    max_entries: usize,
+) -> WalkState {
+    if counter.load(Ordering::Relaxed) >= max_entries {
+        return WalkState::Quit;
+    }
+
+    if entry.file_type().is_some_and(|ft| ft.is_file()) {
+        if is_hidden(entry.file_name()) {
+            return WalkState::Continue;
+        }
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    batch.push(entry.into_path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    fn tree(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        for (path, contents) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    fn walk(root: &Path, max_depth: Option<usize>, respect_gitignore: bool) -> Vec<String> {
+        let walker =
+            ParallelWalker::new(root.to_path_buf(), max_depth, usize::MAX, respect_gitignore);
+        let mut paths: Vec<String> = walker
+            .spawn()
+            .iter()
+            .flatten()
+            .filter_map(|path| {
+                let relative = path.strip_prefix(root).ok()?.to_string_lossy().into_owned();
+                (!relative.is_empty() && !relative.starts_with(".git")).then_some(relative)
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn ignore_file_takes_precedence_over_gitignore() {
+        let dir = tree(&[
+            (".gitignore", "*.log\nbuild/\n"),
+            (".ignore", "!keep.log\nnotes.txt\n"),
+            ("debug.log", ""),
+            ("keep.log", ""),
+            ("notes.txt", ""),
+            ("main.rs", ""),
+            ("build/out.o", ""),
+        ]);
+        assert_eq!(walk(dir.path(), None, true), ["keep.log", "main.rs"]);
+    }
+
+    #[test]
+    fn nested_gitignore_and_info_exclude_apply() {
+        let dir = tree(&[
+            (".git/info/exclude", "secret\n"),
+            ("secret", ""),
+            ("src/.gitignore", "*.tmp\n"),
+            ("src/lib.rs", ""),
+            ("src/scratch.tmp", ""),
+            ("scratch.tmp", ""),
+        ]);
+        assert_eq!(
+            walk(dir.path(), None, true),
+            ["scratch.tmp", "src", "src/lib.rs"]
+        );
+    }
+
+    #[test]
+    fn no_ignore_lists_ignored_files() {
+        let dir = tree(&[
+            (".gitignore", "*.log\n"),
+            ("debug.log", ""),
+            ("main.rs", ""),
+        ]);
+        assert_eq!(walk(dir.path(), None, false), ["debug.log", "main.rs"]);
+    }
+
+    #[test]
+    fn stops_at_max_depth() {
+        let dir = tree(&[("a/b/c.txt", ""), ("top.txt", "")]);
+        assert_eq!(walk(dir.path(), Some(1), true), ["a", "top.txt"]);
+        assert_eq!(walk(dir.path(), Some(2), true), ["a", "a/b", "top.txt"]);
+    }
+
+    #[test]
+    fn walks_every_level_without_max_depth() {
+        let dir = tree(&[("a/b/c.txt", ""), ("top.txt", "")]);
+        assert_eq!(
+            walk(dir.path(), None, true),
+            ["a", "a/b", "a/b/c.txt", "top.txt"]
+        );
+        assert!(walk(dir.path(), Some(0), true).is_empty());
+    }
+
+    #[test]
+    fn sends_entries_in_bounded_batches() {
+        let names: Vec<String> = (0..BATCH_SIZE * 3).map(|i| format!("{}.txt", i)).collect();
+        let files: Vec<(&str, &str)> = names.iter().map(|name| (name.as_str(), "")).collect();
+        let dir = tree(&files);
+
+        let batches: Vec<Vec<PathBuf>> =
+            ParallelWalker::new(dir.path().to_path_buf(), None, usize::MAX, false)
+                .spawn()
+                .iter()
+                .collect();
+        assert!(batches.len() >= 3);
+        assert!(batches.iter().all(|batch| batch.len() <= BATCH_SIZE));
+
+        let mut paths: Vec<PathBuf> = batches.into_iter().flatten().collect();
+        let total = paths.len();
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), total);
+        assert_eq!(
+            paths
+                .iter()
+                .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+                .count(),
+            names.len()
+        );
+    }
+
+    #[test]
+    fn stops_after_max_entries_files() {
+        let names: Vec<String> = (0..1000)
+            .map(|i| format!("d{}/{}.txt", i % 10, i))
+            .collect();
+        let files: Vec<(&str, &str)> = names.iter().map(|name| (name.as_str(), "")).collect();
+        let dir = tree(&files);
+
+        let found = ParallelWalker::new(dir.path().to_path_buf(), None, 10, false)
+            .spawn()
+            .iter()
+            .flatten()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+            .count();
+        // Walker threads check the limit before counting, so each may add
+        // one more file.
+        assert!((10..=10 + num_cpus::get()).contains(&found), "{}", found);
+    }
+}
@@ -16,6 +16,8 @@ use config::Config;
 use error::Result;
 use plugin::PluginManager;
 use utils::color::set_theme;
+use utils::size::{set_size_style, SizeStyle};
+use utils::time::set_time_style;
 
 fn main() -> Result<()> {
     let (mut config, config_error) = load_config()?;
@@ -24,6 +26,16 @@ fn main() -> Result<()> {
 
     let args = Args::parse(&config);
     theme::set_no_color(args.no_color);
+    set_size_style(SizeStyle {
+        format: args.size_format,
+        precision: args.size_precision,
+        disk_usage: args.disk_usage,
+    });
+    set_time_style(args.time_style.clone());
+
+    if let Some(Command::PluginHost(library)) = &args.command {
+        return plugin::host::serve(std::path::Path::new(library));
+    }
 
     if let Some(Command::Clean) = args.command {
         println!("🔄 Starting plugin cleaning...");
@@ -44,6 +56,10 @@ fn load_config() -> Result<(Config, Option<error::LlaError>)> {
 
 fn initialize_plugin_manager(args: &Args, config: &Config) -> Result<PluginManager> {
     let mut plugin_manager = PluginManager::new(config.clone());
+    if let Some(Command::PluginAction(..)) = args.command {
+        // Actions may prompt on the terminal, which the plugin host can't.
+        plugin_manager = plugin_manager.in_process();
+    }
     plugin_manager.discover_plugins(&args.plugins_dir)?;
     Ok(plugin_manager)
 }
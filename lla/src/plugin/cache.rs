@@ -0,0 +1,90 @@
+use super::PluginInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const CACHE_FILE: &str = ".plugin-cache.json";
+
+/// What out-of-process plugins announced when they were last loaded, so a
+/// run doesn't have to start every plugin process just to ask for its name
+/// and formats. Entries are dropped when the plugin file or `lla` changes.
+#[derive(Default, Serialize, Deserialize)]
+pub struct PluginCache {
+    entries: HashMap<PathBuf, CacheEntry>,
+    #[serde(skip)]
+    seen: HashSet<PathBuf>,
+    #[serde(skip)]
+    changed: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    stamp: FileStamp,
+    name: String,
+    info: PluginInfo,
+}
+
+#[derive(PartialEq, Serialize, Deserialize)]
+struct FileStamp {
+    lla_version: String,
+    len: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            lla_version: env!("CARGO_PKG_VERSION").to_string(),
+            len: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        })
+    }
+}
+
+impl PluginCache {
+    pub fn load(plugin_dir: &Path) -> Self {
+        fs::read(plugin_dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+
+    /// The cached name and details of the plugin at `path`, if it hasn't
+    /// changed since they were cached.
+    pub fn get(&mut self, path: &Path) -> Option<(String, PluginInfo)> {
+        self.seen.insert(path.to_path_buf());
+        let entry = self.entries.get(path)?;
+        if FileStamp::of(path).as_ref() != Some(&entry.stamp) {
+            return None;
+        }
+        Some((entry.name.clone(), entry.info.clone()))
+    }
+
+    pub fn insert(&mut self, path: &Path, name: String, info: PluginInfo) {
+        let Some(stamp) = FileStamp::of(path) else {
+            return;
+        };
+        self.seen.insert(path.to_path_buf());
+        self.entries
+            .insert(path.to_path_buf(), CacheEntry { stamp, name, info });
+        self.changed = true;
+    }
+
+    /// Writes the cache back if it changed, forgetting plugins that are gone.
+    pub fn save(mut self, plugin_dir: &Path) {
+        let before = self.entries.len();
+        self.entries.retain(|path, _| self.seen.contains(path));
+        if !self.changed && self.entries.len() == before {
+            return;
+        }
+        if let Ok(data) = serde_json::to_vec(&self) {
+            let _ = fs::write(plugin_dir.join(CACHE_FILE), data);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use super::call_library;
+use super::process::{read_frame, write_frame};
+use crate::error::{LlaError, Result};
+use libloading::Library;
+use lla_plugin_interface::{PluginApi, MIN_PLUGIN_API_VERSION};
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::os::unix::io::FromRawFd;
+use std::path::Path;
+
+/// Serves the library plugin at `path` over stdin and stdout for
+/// `lla plugin-host`, so a crash in the plugin only ends this process.
+pub fn serve(path: &Path) -> Result<()> {
+    // Plugins print from actions and spinners, so keep the original stdout
+    // for responses and send everything else to stderr.
+    let mut output = unsafe {
+        let fd = libc::dup(libc::STDOUT_FILENO);
+        if fd < 0 || libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        File::from_raw_fd(fd)
+    };
+
+    let library = unsafe { Library::new(path) }
+        .map_err(|e| LlaError::Plugin(format!("Failed to load plugin {:?}: {}", path, e)))?;
+    let api = unsafe {
+        let create_fn = library
+            .get::<unsafe fn() -> *mut PluginApi>(b"_plugin_create")
+            .map_err(|e| {
+                LlaError::Plugin(format!(
+                    "Plugin doesn't have a create function {:?}: {}",
+                    path, e
+                ))
+            })?;
+        create_fn()
+    };
+    let version = unsafe { (*api).version };
+    if version < MIN_PLUGIN_API_VERSION {
+        return Err(LlaError::Plugin(format!(
+            "Plugin version mismatch for {:?}: expected at least {}, got {}",
+            path, MIN_PLUGIN_API_VERSION, version
+        )));
+    }
+
+    let mut input = BufReader::new(io::stdin().lock());
+    while let Some(request) = read_frame(&mut input)? {
+        let response = unsafe { call_library(api, &request) };
+        write_frame(&mut output, &response)?;
+    }
+    Ok(())
+}
@@ -1,25 +1,77 @@
 use crate::config::Config;
 use crate::error::{LlaError, Result};
+use cache::PluginCache;
 use dashmap::DashMap;
 use libloading::Library;
 use lla_plugin_interface::{
+    capability,
     proto::{self, plugin_message::Message, PluginMessage},
-    PluginApi, CURRENT_PLUGIN_API_VERSION,
+    Handshake, PluginApi, CURRENT_PLUGIN_API_VERSION, MIN_PLUGIN_API_VERSION,
 };
 use once_cell::sync::Lazy;
+use process::PluginProcess;
 use prost::Message as _;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::os::unix::fs::MetadataExt;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+mod cache;
+pub mod host;
+mod process;
 
 type DecorationCache = DashMap<(String, String), HashMap<String, String>>;
 static DECORATION_CACHE: Lazy<DecorationCache> = Lazy::new(DashMap::new);
 
+enum PluginBackend {
+    Library {
+        api: *mut PluginApi,
+        _library: Library,
+    },
+    Process(PluginProcess),
+}
+
+/// Calls a loaded plugin library with an encoded `PluginMessage`.
+unsafe fn call_library(api: *mut PluginApi, request: &[u8]) -> Vec<u8> {
+    let raw_response =
+        ((*api).handle_request)(std::ptr::null_mut(), request.as_ptr(), request.len());
+    Vec::from_raw_parts(raw_response.ptr, raw_response.len, raw_response.capacity)
+}
+
+/// Capabilities this host makes use of, announced in the handshake.
+const HOST_CAPABILITIES: &[&str] = &[
+    capability::DECORATE_BATCH,
+    capability::FORMATTERS,
+    capability::SORTERS,
+    capability::FILTERS,
+];
+
+/// Extension marking executables in the plugins directory as plugin
+/// processes, e.g. `tags.lla-plugin`.
+const PROCESS_PLUGIN_EXTENSION: &str = "lla-plugin";
+
+/// What a plugin announced when it was loaded.
+#[derive(Clone, Serialize, Deserialize)]
+struct PluginInfo {
+    api_version: u32,
+    /// `None` for plugins that predate the handshake.
+    capabilities: Option<HashSet<String>>,
+    formats: Vec<String>,
+    formatters: Vec<String>,
+    sorters: Vec<String>,
+    filters: Vec<String>,
+}
+
 pub struct PluginManager {
-    plugins: HashMap<String, (Library, *mut PluginApi)>,
+    plugins: HashMap<String, PluginBackend>,
     loaded_paths: HashSet<PathBuf>,
+    plugin_info: HashMap<String, PluginInfo>,
+    no_batch_support: HashSet<String>,
     pub enabled_plugins: HashSet<String>,
+    isolate: bool,
+    cache: Option<PluginCache>,
     config: Config,
 }
 
@@ -29,11 +81,22 @@ impl PluginManager {
         PluginManager {
             plugins: HashMap::new(),
             loaded_paths: HashSet::new(),
+            plugin_info: HashMap::new(),
+            no_batch_support: HashSet::new(),
             enabled_plugins,
+            isolate: config.plugins.isolate,
+            cache: None,
             config,
         }
     }
 
+    /// Loads library plugins in this process even when `plugins.isolate` is
+    /// set, e.g. for actions that prompt on the terminal.
+    pub fn in_process(mut self) -> Self {
+        self.isolate = false;
+        self
+    }
+
     fn _convert_metadata(metadata: &std::fs::Metadata) -> proto::EntryMetadata {
         proto::EntryMetadata {
             size: metadata.len(),
@@ -71,25 +134,23 @@ impl PluginManager {
     }
 
     fn send_request(&self, plugin_name: &str, request: PluginMessage) -> Result<PluginMessage> {
-        if let Some((_, api)) = self.plugins.get(plugin_name) {
-            let mut buf = Vec::with_capacity(request.encoded_len());
-            request.encode(&mut buf).unwrap();
-
-            unsafe {
-                let raw_response =
-                    ((**api).handle_request)(std::ptr::null_mut(), buf.as_ptr(), buf.len());
-                let response_vec =
-                    Vec::from_raw_parts(raw_response.ptr, raw_response.len, raw_response.capacity);
-                let response_msg = proto::PluginMessage::decode(&response_vec[..])
-                    .map_err(|e| LlaError::Plugin(format!("Failed to decode response: {}", e)))?;
-                Ok(response_msg)
-            }
-        } else {
-            Err(LlaError::Plugin(format!(
-                "Plugin '{}' not found",
-                plugin_name
-            )))
-        }
+        let backend = self
+            .plugins
+            .get(plugin_name)
+            .ok_or_else(|| LlaError::Plugin(format!("Plugin '{}' not found", plugin_name)))?;
+        Self::send_to_backend(backend, request)
+    }
+
+    fn send_to_backend(backend: &PluginBackend, request: PluginMessage) -> Result<PluginMessage> {
+        let mut buf = Vec::with_capacity(request.encoded_len());
+        request.encode(&mut buf).unwrap();
+
+        let response_vec = match backend {
+            PluginBackend::Library { api, .. } => unsafe { call_library(*api, &buf) },
+            PluginBackend::Process(process) => process.request(&buf)?,
+        };
+        proto::PluginMessage::decode(&response_vec[..])
+            .map_err(|e| LlaError::Plugin(format!("Failed to decode response: {}", e)))
     }
 
     pub fn perform_plugin_action(
@@ -128,6 +189,110 @@ impl PluginManager {
         }
     }
 
+    /// Renders `entries` with a formatter a plugin registered, for
+    /// `--format <plugin>:<formatter>`.
+    pub fn format_entries(
+        &mut self,
+        plugin_name: &str,
+        formatter: &str,
+        entries: &[proto::DecoratedEntry],
+    ) -> Result<String> {
+        if !self.enabled_plugins.contains(plugin_name) {
+            return Err(LlaError::Plugin(format!(
+                "Plugin '{}' is not enabled",
+                plugin_name
+            )));
+        }
+        let formatters = self
+            .plugin_info
+            .get(plugin_name)
+            .map(|info| info.formatters.as_slice())
+            .unwrap_or_default();
+        if !formatters.iter().any(|f| f == formatter) {
+            return Err(LlaError::Plugin(if formatters.is_empty() {
+                format!("Plugin '{}' has no formatters", plugin_name)
+            } else {
+                format!(
+                    "Plugin '{}' has no formatter '{}', available: {}",
+                    plugin_name,
+                    formatter,
+                    formatters.join(", ")
+                )
+            }));
+        }
+
+        let request = PluginMessage {
+            message: Some(Message::FormatEntries(proto::FormatEntriesRequest {
+                formatter: formatter.to_string(),
+                entries: entries.to_vec(),
+            })),
+        };
+        match self.send_request(plugin_name, request)?.message {
+            Some(Message::FormattedEntriesResponse(output)) => Ok(output),
+            Some(Message::ErrorResponse(e)) => Err(LlaError::Plugin(format!(
+                "Plugin '{}' failed to format entries: {}",
+                plugin_name, e
+            ))),
+            _ => Err(LlaError::Plugin("Invalid response type".to_string())),
+        }
+    }
+
+    /// Asks a plugin for the sort keys of `entries` under its sorter `sorter`,
+    /// one per entry. Empty keys mean the entry has no value.
+    pub fn sort_keys(
+        &mut self,
+        plugin_name: &str,
+        sorter: &str,
+        entries: &[proto::DecoratedEntry],
+    ) -> Result<Vec<String>> {
+        let request = PluginMessage {
+            message: Some(Message::SortKeys(proto::SortKeysRequest {
+                sorter: sorter.to_string(),
+                entries: entries.to_vec(),
+            })),
+        };
+        match self.send_request(plugin_name, request)?.message {
+            Some(Message::SortKeysResponse(response)) if response.keys.len() == entries.len() => {
+                Ok(response.keys)
+            }
+            Some(Message::ErrorResponse(e)) => Err(LlaError::Plugin(format!(
+                "Plugin '{}' failed to sort entries: {}",
+                plugin_name, e
+            ))),
+            _ => Err(LlaError::Plugin("Invalid response type".to_string())),
+        }
+    }
+
+    /// Asks a plugin which of `entries` match its filter `filter` with
+    /// `argument`, one result per entry.
+    pub fn filter_entries(
+        &mut self,
+        plugin_name: &str,
+        filter: &str,
+        argument: &str,
+        entries: &[proto::DecoratedEntry],
+    ) -> Result<Vec<bool>> {
+        let request = PluginMessage {
+            message: Some(Message::FilterEntries(proto::FilterEntriesRequest {
+                filter: filter.to_string(),
+                argument: argument.to_string(),
+                entries: entries.to_vec(),
+            })),
+        };
+        match self.send_request(plugin_name, request)?.message {
+            Some(Message::FilterEntriesResponse(response))
+                if response.matches.len() == entries.len() =>
+            {
+                Ok(response.matches)
+            }
+            Some(Message::ErrorResponse(e)) => Err(LlaError::Plugin(format!(
+                "Plugin '{}' failed to filter entries: {}",
+                plugin_name, e
+            ))),
+            _ => Err(LlaError::Plugin("Invalid response type".to_string())),
+        }
+    }
+
     pub fn list_plugins(&mut self) -> Vec<(String, String, String)> {
         let mut result = Vec::new();
         for plugin_name in self.plugins.keys() {
@@ -187,62 +352,40 @@ impl PluginManager {
             return Ok(());
         }
 
+        if self.isolate {
+            let process = PluginProcess::new(
+                &path,
+                std::env::current_exe()?,
+                vec!["plugin-host".into(), path.clone().into()],
+                Duration::from_millis(self.config.plugins.timeout_ms),
+                self.config.plugins.max_restarts,
+            );
+            self.register_process(path, process);
+            return Ok(());
+        }
+
         unsafe {
             match Library::new(&path) {
                 Ok(library) => {
                     match library.get::<unsafe fn() -> *mut PluginApi>(b"_plugin_create") {
                         Ok(create_fn) => {
                             let api = create_fn();
-                            if (*api).version != CURRENT_PLUGIN_API_VERSION {
+                            if (*api).version < MIN_PLUGIN_API_VERSION {
                                 eprintln!(
-                                    "⚠️ Plugin version mismatch for {:?}: expected {}, got {} run `lla clean` to remove invalid plugins",
+                                    "⚠️ Plugin version mismatch for {:?}: expected at least {}, got {} run `lla clean` to remove invalid plugins",
                                     path,
-                                    CURRENT_PLUGIN_API_VERSION,
+                                    MIN_PLUGIN_API_VERSION,
                                     (*api).version
                                 );
                                 return Ok(());
                             }
-
-                            let request = PluginMessage {
-                                message: Some(Message::GetName(true)),
-                            };
-                            let mut buf = Vec::with_capacity(request.encoded_len());
-                            request.encode(&mut buf).unwrap();
-
-                            match ((*api).handle_request)(
-                                std::ptr::null_mut(),
-                                buf.as_ptr(),
-                                buf.len(),
-                            ) {
-                                raw_response => {
-                                    let response_vec = Vec::from_raw_parts(
-                                        raw_response.ptr,
-                                        raw_response.len,
-                                        raw_response.capacity,
-                                    );
-                                    match proto::PluginMessage::decode(&response_vec[..]) {
-                                        Ok(response_msg) => match response_msg.message {
-                                            Some(Message::NameResponse(name)) => {
-                                                if let std::collections::hash_map::Entry::Vacant(
-                                                    e,
-                                                ) = self.plugins.entry(name)
-                                                {
-                                                    e.insert((library, api));
-                                                    self.loaded_paths.insert(path);
-                                                }
-                                            }
-                                            _ => eprintln!(
-                                                "⚠️ Failed to get plugin name for {:?}",
-                                                path
-                                            ),
-                                        },
-                                        Err(e) => eprintln!(
-                                            "⚠️ Failed to decode response for {:?}: {}",
-                                            path, e
-                                        ),
-                                    }
-                                }
-                            }
+                            self.register(
+                                path,
+                                PluginBackend::Library {
+                                    api,
+                                    _library: library,
+                                },
+                            );
                         }
                         Err(e) => {
                             eprintln!("⚠️ Plugin doesn't have a create function {:?}: {}", path, e)
@@ -255,6 +398,236 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Loads an executable that speaks the plugin protocol over stdin and
+    /// stdout, framing each `PluginMessage` with its varint length.
+    pub fn load_process_plugin<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref().canonicalize()?;
+        if self.loaded_paths.contains(&path) {
+            return Ok(());
+        }
+
+        let process = PluginProcess::new(
+            &path,
+            path.clone(),
+            Vec::new(),
+            Duration::from_millis(self.config.plugins.timeout_ms),
+            self.config.plugins.max_restarts,
+        );
+        self.register_process(path, process);
+        Ok(())
+    }
+
+    /// Registers a plugin process, using what it announced on an earlier run
+    /// when it hasn't changed since, so the process is only started once it
+    /// is actually used.
+    fn register_process(&mut self, path: PathBuf, process: PluginProcess) {
+        if let Some((name, info)) = self.cache.as_mut().and_then(|cache| cache.get(&path)) {
+            if let std::collections::hash_map::Entry::Vacant(e) = self.plugins.entry(name.clone()) {
+                e.insert(PluginBackend::Process(process));
+                self.loaded_paths.insert(path);
+                self.plugin_info.insert(name, info);
+            }
+            return;
+        }
+
+        if let Some(name) = self.register(path.clone(), PluginBackend::Process(process)) {
+            if let (Some(cache), Some(info)) = (self.cache.as_mut(), self.plugin_info.get(&name)) {
+                if self.loaded_paths.contains(&path) {
+                    cache.insert(&path, name.clone(), info.clone());
+                }
+            }
+            // Only keep processes of plugins this run is likely to use.
+            if !self.enabled_plugins.contains(&name) {
+                if let Some(PluginBackend::Process(process)) = self.plugins.get(&name) {
+                    process.stop();
+                }
+            }
+        }
+    }
+
+    /// Registers a plugin under the name it reports after the handshake and
+    /// caches its supported formats, returning the name.
+    fn register(&mut self, path: PathBuf, backend: PluginBackend) -> Option<String> {
+        let (api_version, capabilities) = match Self::handshake(&backend) {
+            Ok(negotiated) => negotiated,
+            Err(e) => {
+                eprintln!(
+                    "⚠️ Plugin {:?} {} run `lla clean` to remove invalid plugins",
+                    path, e
+                );
+                return None;
+            }
+        };
+
+        let request = PluginMessage {
+            message: Some(Message::GetName(true)),
+        };
+        let name = match Self::send_to_backend(&backend, request) {
+            Ok(PluginMessage {
+                message: Some(Message::NameResponse(name)),
+            }) => name,
+            Ok(_) => {
+                eprintln!("⚠️ Failed to get plugin name for {:?}", path);
+                return None;
+            }
+            Err(e) => {
+                eprintln!("⚠️ Failed to get plugin name for {:?}: {}", path, e);
+                return None;
+            }
+        };
+
+        if let std::collections::hash_map::Entry::Vacant(e) = self.plugins.entry(name.clone()) {
+            e.insert(backend);
+            self.loaded_paths.insert(path);
+            let formats = self.query_supported_formats(&name);
+            let query = |capability: &str, request: Message| {
+                if capabilities
+                    .as_ref()
+                    .is_some_and(|c| c.contains(capability))
+                {
+                    self.query_names(&name, request)
+                } else {
+                    Vec::new()
+                }
+            };
+            let formatters = query(capability::FORMATTERS, Message::GetFormatters(true));
+            let sorters = query(capability::SORTERS, Message::GetSorters(true));
+            let filters = query(capability::FILTERS, Message::GetFilters(true));
+            self.plugin_info.insert(
+                name.clone(),
+                PluginInfo {
+                    api_version,
+                    capabilities,
+                    formats,
+                    formatters,
+                    sorters,
+                    filters,
+                },
+            );
+        }
+        Some(name)
+    }
+
+    /// Exchanges API versions and capabilities with a plugin, returning the
+    /// negotiated version and the capabilities both sides support. Plugins
+    /// that predate the handshake answer with an error and are treated as
+    /// the oldest API version, with unknown capabilities.
+    fn handshake(
+        backend: &PluginBackend,
+    ) -> std::result::Result<(u32, Option<HashSet<String>>), String> {
+        let request = PluginMessage {
+            message: Some(Message::Handshake(
+                Handshake::current(HOST_CAPABILITIES).into(),
+            )),
+        };
+        let handshake = match Self::send_to_backend(backend, request) {
+            Ok(PluginMessage {
+                message: Some(Message::HandshakeResponse(handshake)),
+            }) => handshake,
+            _ => return Ok((MIN_PLUGIN_API_VERSION, None)),
+        };
+
+        let version = handshake.max_api_version.min(CURRENT_PLUGIN_API_VERSION);
+        if version < handshake.min_api_version.max(MIN_PLUGIN_API_VERSION) {
+            return Err(format!(
+                "supports API versions {}-{}, but lla supports {}-{}",
+                handshake.min_api_version,
+                handshake.max_api_version,
+                MIN_PLUGIN_API_VERSION,
+                CURRENT_PLUGIN_API_VERSION
+            ));
+        }
+        let capabilities = handshake
+            .capabilities
+            .into_iter()
+            .filter(|c| HOST_CAPABILITIES.contains(&c.as_str()))
+            .collect();
+        Ok((version, Some(capabilities)))
+    }
+
+    /// The negotiated API version and the capabilities of a loaded plugin,
+    /// with the names of its formatters, sorters and filters.
+    pub fn plugin_api(&self, name: &str) -> Option<(u32, Vec<String>)> {
+        let info = self.plugin_info.get(name)?;
+        let mut capabilities: Vec<String> = info
+            .capabilities
+            .iter()
+            .flatten()
+            .map(|capability| {
+                let names = match capability.as_str() {
+                    capability::FORMATTERS => &info.formatters,
+                    capability::SORTERS => &info.sorters,
+                    capability::FILTERS => &info.filters,
+                    _ => return capability.clone(),
+                };
+                format!("{} ({})", capability, names.join(", "))
+            })
+            .collect();
+        capabilities.sort();
+        Some((info.api_version, capabilities))
+    }
+
+    /// The enabled plugin providing the sorter `name`, if any.
+    pub fn sorter_provider(&self, name: &str) -> Option<String> {
+        self.provider(name, |info| &info.sorters)
+    }
+
+    /// The enabled plugin providing the filter `name`, if any.
+    pub fn filter_provider(&self, name: &str) -> Option<String> {
+        self.provider(name, |info| &info.filters)
+    }
+
+    /// A loaded plugin providing the filter `name` that isn't enabled.
+    pub fn disabled_filter_provider(&self, name: &str) -> Option<String> {
+        let mut plugins: Vec<&String> = self
+            .plugin_info
+            .iter()
+            .filter(|(plugin, info)| {
+                !self.enabled_plugins.contains(*plugin) && info.filters.iter().any(|n| n == name)
+            })
+            .map(|(plugin, _)| plugin)
+            .collect();
+        plugins.sort();
+        plugins.first().map(|plugin| plugin.to_string())
+    }
+
+    /// Names of the sorters provided by enabled plugins.
+    pub fn sorter_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .enabled_plugins
+            .iter()
+            .filter_map(|plugin| self.plugin_info.get(plugin))
+            .flat_map(|info| info.sorters.iter().cloned())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn provider(&self, name: &str, names: impl Fn(&PluginInfo) -> &Vec<String>) -> Option<String> {
+        let mut plugins: Vec<&String> = self
+            .enabled_plugins
+            .iter()
+            .filter(|plugin| {
+                self.plugin_info
+                    .get(*plugin)
+                    .is_some_and(|info| names(info).iter().any(|n| n == name))
+            })
+            .collect();
+        plugins.sort();
+        plugins.first().map(|plugin| plugin.to_string())
+    }
+
+    /// Whether a plugin announced `capability`, or `None` if it predates the
+    /// handshake and the feature has to be tried.
+    fn supports(&self, name: &str, capability: &str) -> Option<bool> {
+        self.plugin_info
+            .get(name)?
+            .capabilities
+            .as_ref()
+            .map(|capabilities| capabilities.contains(capability))
+    }
+
     pub fn discover_plugins<P: AsRef<Path>>(&mut self, plugin_dir: P) -> Result<()> {
         let plugin_dir = plugin_dir.as_ref();
         if !plugin_dir.is_dir() {
@@ -266,18 +639,24 @@ impl PluginManager {
             })?;
         }
 
+        self.cache = Some(PluginCache::load(plugin_dir));
         for entry in fs::read_dir(plugin_dir)? {
             let entry = entry?;
             let path = entry.path();
-            if let Some(extension) = path.extension() {
-                if extension == "so" || extension == "dll" || extension == "dylib" {
-                    match self.load_plugin(&path) {
-                        Ok(_) => (),
-                        Err(e) => eprintln!("Failed to load plugin {:?}: {}", path, e),
-                    }
-                }
+            let result = if is_plugin_library(&path) {
+                self.load_plugin(&path)
+            } else if is_process_plugin(&path) {
+                self.load_process_plugin(&path)
+            } else {
+                continue;
+            };
+            if let Err(e) = result {
+                eprintln!("Failed to load plugin {:?}: {}", path, e);
             }
         }
+        if let Some(cache) = self.cache.take() {
+            cache.save(plugin_dir);
+        }
 
         Ok(())
     }
@@ -302,99 +681,178 @@ impl PluginManager {
         }
     }
 
-    pub fn decorate_entry(&mut self, entry: &mut proto::DecoratedEntry, format: &str) {
-        if self.enabled_plugins.is_empty() || (format != "default" && format != "long") {
-            return;
+    pub fn decorates_format(format: &str) -> bool {
+        format == "default" || format == "long"
+    }
+
+    fn query_supported_formats(&self, name: &str) -> Vec<String> {
+        let request = PluginMessage {
+            message: Some(Message::GetSupportedFormats(true)),
+        };
+        match self
+            .send_request(name, request)
+            .map(|response| response.message)
+        {
+            Ok(Some(Message::FormatsResponse(response))) => response.formats,
+            _ => Vec::new(),
         }
+    }
 
-        let path_str = entry.path.clone();
-        let cache_key = (path_str.clone(), format.to_string());
-        if let Some(fields) = DECORATION_CACHE.get(&cache_key) {
-            entry
-                .custom_fields
-                .extend(fields.value().iter().map(|(k, v)| (k.clone(), v.clone())));
-            return;
+    /// Asks a plugin for the names of its formatters, sorters or filters.
+    fn query_names(&self, name: &str, request: Message) -> Vec<String> {
+        let request = PluginMessage {
+            message: Some(request),
+        };
+        match self
+            .send_request(name, request)
+            .map(|response| response.message)
+        {
+            Ok(Some(Message::FormattersResponse(response))) => response.formatters,
+            Ok(Some(Message::SortersResponse(response))) => response.sorters,
+            Ok(Some(Message::FiltersResponse(response))) => response.filters,
+            _ => Vec::new(),
         }
+    }
 
-        let supported_names: Vec<_> = {
-            let mut names = Vec::new();
-            for name in self.enabled_plugins.iter() {
-                let request = PluginMessage {
-                    message: Some(Message::GetSupportedFormats(true)),
-                };
+    /// Enabled plugins that decorate entries in `format`, using the formats
+    /// each plugin reported when it was loaded.
+    fn plugins_for_format(&self, format: &str) -> Vec<String> {
+        self.enabled_plugins
+            .iter()
+            .filter(|name| {
+                self.plugin_info
+                    .get(*name)
+                    .is_some_and(|info| info.formats.iter().any(|f| f == format))
+            })
+            .cloned()
+            .collect()
+    }
 
-                if let Ok(response) = self.send_request(name, request) {
-                    if let Some(Message::FormatsResponse(formats_response)) = response.message {
-                        if formats_response.formats.contains(&format.to_string()) {
-                            names.push(name.clone());
-                        }
-                    }
-                }
+    pub fn decorate_entry(&mut self, entry: &mut proto::DecoratedEntry, format: &str) {
+        self.decorate_entries(std::slice::from_mut(entry), format);
+    }
+
+    /// Decorates `entries` with one `DecorateBatch` request per plugin. Plugins
+    /// that predate batch decoration are sent one `Decorate` request per entry.
+    pub fn decorate_entries(&mut self, entries: &mut [proto::DecoratedEntry], format: &str) {
+        if self.enabled_plugins.is_empty() || !Self::decorates_format(format) {
+            return;
+        }
+
+        let mut pending = Vec::new();
+        for (index, entry) in entries.iter_mut().enumerate() {
+            let cache_key = (entry.path.clone(), format.to_string());
+            match DECORATION_CACHE.get(&cache_key) {
+                Some(fields) => entry
+                    .custom_fields
+                    .extend(fields.value().iter().map(|(k, v)| (k.clone(), v.clone()))),
+                None => pending.push(index),
             }
-            names
-        };
+        }
+        if pending.is_empty() {
+            return;
+        }
 
+        let supported_names = self.plugins_for_format(format);
         if supported_names.is_empty() {
             return;
         }
 
-        let mut new_decorations = HashMap::with_capacity(supported_names.len() * 2);
+        let batch: Vec<_> = pending
+            .iter()
+            .map(|&index| entries[index].clone())
+            .collect();
+        let mut new_decorations = vec![HashMap::new(); batch.len()];
         for name in supported_names {
-            let request = PluginMessage {
-                message: Some(Message::Decorate(entry.clone())),
-            };
-
-            if let Ok(response) = self.send_request(&name, request) {
-                if let Some(Message::DecoratedResponse(decorated)) = response.message {
-                    new_decorations.extend(decorated.custom_fields);
+            match self.decorate_batch(&name, &batch) {
+                Some(decorated) => {
+                    for (fields, entry) in new_decorations.iter_mut().zip(decorated) {
+                        fields.extend(entry.custom_fields);
+                    }
+                }
+                None => {
+                    for (fields, entry) in new_decorations.iter_mut().zip(&batch) {
+                        let request = PluginMessage {
+                            message: Some(Message::Decorate(entry.clone())),
+                        };
+                        if let Ok(response) = self.send_request(&name, request) {
+                            if let Some(Message::DecoratedResponse(decorated)) = response.message {
+                                fields.extend(decorated.custom_fields);
+                            }
+                        }
+                    }
                 }
             }
         }
 
-        if !new_decorations.is_empty() {
+        for (index, fields) in pending.into_iter().zip(new_decorations) {
+            if fields.is_empty() {
+                continue;
+            }
+            let entry = &mut entries[index];
             entry
                 .custom_fields
-                .extend(new_decorations.iter().map(|(k, v)| (k.clone(), v.clone())));
-            DECORATION_CACHE.insert(cache_key, new_decorations);
+                .extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+            DECORATION_CACHE.insert((entry.path.clone(), format.to_string()), fields);
+        }
+    }
+
+    /// Sends `entries` to a plugin as one batch, returning `None` if the
+    /// plugin doesn't understand `DecorateBatch` or the request failed.
+    fn decorate_batch(
+        &mut self,
+        name: &str,
+        entries: &[proto::DecoratedEntry],
+    ) -> Option<Vec<proto::DecoratedEntry>> {
+        let supported = self
+            .supports(name, capability::DECORATE_BATCH)
+            .unwrap_or(!self.no_batch_support.contains(name));
+        if !supported {
+            return None;
+        }
+
+        let request = PluginMessage {
+            message: Some(Message::DecorateBatch(proto::DecorateBatch {
+                entries: entries.to_vec(),
+            })),
+        };
+        match self
+            .send_request(name, request)
+            .map(|response| response.message)
+        {
+            Ok(Some(Message::DecoratedBatchResponse(batch)))
+                if batch.entries.len() == entries.len() =>
+            {
+                Some(batch.entries)
+            }
+            // A plugin process that crashed or timed out is retried one entry
+            // at a time, so only the entry that broke it loses its fields.
+            Err(_) => None,
+            Ok(_) => {
+                self.no_batch_support.insert(name.to_string());
+                None
+            }
         }
     }
 
     pub fn format_fields(&mut self, entry: &proto::DecoratedEntry, format: &str) -> Vec<String> {
-        if self.enabled_plugins.is_empty() || (format != "default" && format != "long") {
+        if self.enabled_plugins.is_empty() || !Self::decorates_format(format) {
             return Vec::new();
         }
 
         let mut result = Vec::with_capacity(self.enabled_plugins.len());
-        for name in self.enabled_plugins.iter() {
-            let supports_format = match self.send_request(
-                name,
-                PluginMessage {
-                    message: Some(Message::GetSupportedFormats(true)),
-                },
-            ) {
-                Ok(response) => {
-                    if let Some(Message::FormatsResponse(formats)) = response.message {
-                        formats.formats.contains(&format.to_string())
-                    } else {
-                        false
-                    }
-                }
-                Err(_) => false,
+        for name in self.plugins_for_format(format) {
+            let request = PluginMessage {
+                message: Some(Message::FormatField(proto::FormatFieldRequest {
+                    entry: Some(entry.clone()),
+                    format: format.to_string(),
+                })),
             };
 
-            if supports_format {
-                let request = PluginMessage {
-                    message: Some(Message::FormatField(proto::FormatFieldRequest {
-                        entry: Some(entry.clone()),
-                        format: format.to_string(),
-                    })),
-                };
-
-                if let Ok(response) = self.send_request(name, request) {
-                    if let Some(Message::FieldResponse(field_response)) = response.message {
-                        if let Some(field) = field_response.field {
-                            result.push(field);
-                        }
+            if let Ok(response) = self.send_request(&name, request) {
+                if let Some(Message::FieldResponse(field_response)) = response.message {
+                    if let Some(field) = field_response.field {
+                        result.push(field);
                     }
                 }
             }
@@ -416,7 +874,7 @@ impl PluginManager {
                 if extension == "so" || extension == "dll" || extension == "dylib" {
                     println!("📦 Checking plugin: {:?}", path);
 
-                    match std::panic::catch_unwind(|| self.validate_plugin(&path)) {
+                    match std::panic::catch_unwind(|| Self::validate_plugin(&path)) {
                         Ok(Ok(true)) => println!("✅ Plugin is valid: {:?}", path),
                         Ok(Ok(false)) => {
                             println!("❌ Plugin is invalid: {:?}", path);
@@ -447,7 +905,7 @@ impl PluginManager {
         Ok(())
     }
 
-    fn validate_plugin<P: AsRef<Path>>(&self, path: P) -> Result<bool> {
+    fn validate_plugin<P: AsRef<Path>>(path: P) -> Result<bool> {
         unsafe {
             let library = match Library::new(path.as_ref()) {
                 Ok(lib) => lib,
@@ -468,7 +926,7 @@ impl PluginManager {
                 return Ok(false);
             }
 
-            if (*api).version != CURRENT_PLUGIN_API_VERSION {
+            if (*api).version < MIN_PLUGIN_API_VERSION {
                 return Ok(false);
             }
 
@@ -509,3 +967,20 @@ impl PluginManager {
         }
     }
 }
+
+fn is_plugin_library(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "so" || extension == "dll" || extension == "dylib")
+}
+
+fn is_process_plugin(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == PROCESS_PLUGIN_EXTENSION)
+        && is_executable(path)
+}
+
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
@@ -0,0 +1,209 @@
+use crate::error::{LlaError, Result};
+use std::cell::RefCell;
+use std::ffi::OsString;
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+const MAX_FRAME_LEN: u64 = 1 << 30;
+
+/// Writes `frame` prefixed with its length as a protobuf varint, the
+/// framing used by `writeDelimitedTo` and friends in other languages.
+pub fn write_frame<W: Write>(writer: &mut W, frame: &[u8]) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(frame.len() + 10);
+    prost::encoding::encode_varint(frame.len() as u64, &mut buf);
+    buf.extend_from_slice(frame);
+    writer.write_all(&buf)?;
+    writer.flush()
+}
+
+/// Reads a frame written by `write_frame`, or `None` at the end of the stream.
+pub fn read_frame<R: Read>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut len = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8];
+        match reader.read_exact(&mut byte) {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof && shift == 0 => return Ok(None),
+            result => result?,
+        }
+        len |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            if len > MAX_FRAME_LEN {
+                break;
+            }
+            let mut frame = vec![0; len as usize];
+            reader.read_exact(&mut frame)?;
+            return Ok(Some(frame));
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "invalid frame length",
+    ))
+}
+
+/// A plugin running as a child process that exchanges `PluginMessage`
+/// frames over its stdin and stdout. The process is started on the first
+/// request and restarted after a crash or timeout, up to `max_restarts`
+/// times, after which the plugin is disabled for the rest of the run.
+pub struct PluginProcess {
+    label: String,
+    program: PathBuf,
+    args: Vec<OsString>,
+    timeout: Duration,
+    max_restarts: u32,
+    state: RefCell<ProcessState>,
+}
+
+#[derive(Default)]
+struct ProcessState {
+    running: Option<Running>,
+    failures: u32,
+    disabled: bool,
+}
+
+struct Running {
+    child: Child,
+    requests: Sender<Vec<u8>>,
+    responses: Receiver<io::Result<Option<Vec<u8>>>>,
+}
+
+impl PluginProcess {
+    pub fn new(
+        label: &Path,
+        program: PathBuf,
+        args: Vec<OsString>,
+        timeout: Duration,
+        max_restarts: u32,
+    ) -> Self {
+        Self {
+            label: label
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| label.to_string_lossy().into_owned()),
+            program,
+            args,
+            timeout,
+            max_restarts,
+            state: RefCell::new(ProcessState::default()),
+        }
+    }
+
+    pub fn request(&self, frame: &[u8]) -> Result<Vec<u8>> {
+        let mut state = self.state.borrow_mut();
+        if state.disabled {
+            return Err(LlaError::Plugin(format!(
+                "Plugin process '{}' is disabled",
+                self.label
+            )));
+        }
+
+        let result = match state.running.take() {
+            Some(running) => Ok(running),
+            None => self.spawn().map_err(|e| format!("failed to start: {}", e)),
+        }
+        .and_then(|mut running| {
+            let response = running.exchange(frame, self.timeout)?;
+            Ok((running, response))
+        });
+
+        match result {
+            Ok((running, response)) => {
+                state.running = Some(running);
+                Ok(response)
+            }
+            Err(reason) => {
+                state.failures += 1;
+                if state.failures > self.max_restarts {
+                    state.disabled = true;
+                    eprintln!(
+                        "⚠️ Plugin '{}' {}, disabled for this run",
+                        self.label, reason
+                    );
+                } else {
+                    eprintln!("⚠️ Plugin '{}' {}, restarting it", self.label, reason);
+                }
+                Err(LlaError::Plugin(format!(
+                    "Plugin process '{}' {}",
+                    self.label, reason
+                )))
+            }
+        }
+    }
+
+    /// Stops the process until the next request, without counting as a failure.
+    pub fn stop(&self) {
+        self.state.borrow_mut().running = None;
+    }
+
+    fn spawn(&self) -> io::Result<Running> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let mut stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+        let (requests, pending) = mpsc::channel::<Vec<u8>>();
+        thread::spawn(move || {
+            for frame in pending {
+                if write_frame(&mut stdin, &frame).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let (replies, responses) = mpsc::channel();
+        thread::spawn(move || loop {
+            let frame = read_frame(&mut stdout);
+            let done = !matches!(frame, Ok(Some(_)));
+            if replies.send(frame).is_err() || done {
+                break;
+            }
+        });
+
+        Ok(Running {
+            child,
+            requests,
+            responses,
+        })
+    }
+}
+
+impl Running {
+    fn exchange(
+        &mut self,
+        frame: &[u8],
+        timeout: Duration,
+    ) -> std::result::Result<Vec<u8>, String> {
+        let _ = self.requests.send(frame.to_vec());
+        match self.responses.recv_timeout(timeout) {
+            Ok(Ok(Some(response))) => Ok(response),
+            Ok(Err(e)) => Err(format!("sent an invalid response: {}", e)),
+            Err(RecvTimeoutError::Timeout) => {
+                Err(format!("timed out after {} ms", timeout.as_millis()))
+            }
+            Ok(Ok(None)) | Err(RecvTimeoutError::Disconnected) => {
+                for _ in 0..10 {
+                    if let Ok(Some(status)) = self.child.try_wait() {
+                        return Err(format!("exited unexpectedly ({})", status));
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err("closed its output".to_string())
+            }
+        }
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -1,52 +1,39 @@
-use super::{compare_dirs_first, FileSorter, SortOptions};
-use crate::error::Result;
-use lla_plugin_interface::proto::DecoratedEntry;
-use rayon::prelude::*;
-use std::path::PathBuf;
+use super::{FileSorter, SortOptions};
+use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata};
+use std::cmp::Ordering;
 
-pub struct DateSorter;
-
-impl FileSorter for DateSorter {
-    fn sort_files_with_metadata(
-        &self,
-        entries: &mut [(PathBuf, &DecoratedEntry)],
-        options: SortOptions,
-    ) -> Result<()> {
-        entries.par_sort_unstable_by(|(path_a, entry_a), (path_b, entry_b)| {
-            if options.dirs_first {
-                let a_is_dir = entry_a.metadata.as_ref().map_or(false, |m| m.is_dir);
-                let b_is_dir = entry_b.metadata.as_ref().map_or(false, |m| m.is_dir);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeKey {
+    Modified,
+    Accessed,
+    Created,
+}
 
-                match (a_is_dir, b_is_dir) {
-                    (true, false) => {
-                        return if options.reverse {
-                            std::cmp::Ordering::Greater
-                        } else {
-                            std::cmp::Ordering::Less
-                        }
-                    }
-                    (false, true) => {
-                        return if options.reverse {
-                            std::cmp::Ordering::Less
-                        } else {
-                            std::cmp::Ordering::Greater
-                        }
-                    }
-                    _ => {}
-                }
-            }
+impl TimeKey {
+    fn timestamp(self, metadata: &EntryMetadata) -> u64 {
+        match self {
+            TimeKey::Modified => metadata.modified,
+            TimeKey::Accessed => metadata.accessed,
+            TimeKey::Created => metadata.created,
+        }
+    }
+}
 
-            let time_a = entry_a.metadata.as_ref().map_or(0, |m| m.modified);
-            let time_b = entry_b.metadata.as_ref().map_or(0, |m| m.modified);
-            let date_order = time_a.cmp(&time_b);
+/// Sorts oldest entries first by the selected timestamp.
+pub struct DateSorter {
+    key: TimeKey,
+}
 
-            if options.reverse {
-                date_order.reverse()
-            } else {
-                date_order
-            }
-        });
+impl DateSorter {
+    pub fn new(key: TimeKey) -> Self {
+        Self { key }
+    }
+}
 
-        Ok(())
+impl FileSorter for DateSorter {
+    fn compare(&self, a: &DecoratedEntry, b: &DecoratedEntry, _options: SortOptions) -> Ordering {
+        let time_a = a.metadata.as_ref().map_or(0, |m| self.key.timestamp(m));
+        let time_b = b.metadata.as_ref().map_or(0, |m| self.key.timestamp(m));
+        time_a.cmp(&time_b)
     }
 }
@@ -0,0 +1,24 @@
+use super::{FileSorter, SortOptions};
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// Sorts by file extension, entries without one first, like `ls -X`.
+pub struct ExtensionSorter;
+
+impl ExtensionSorter {
+    fn extension(entry: &DecoratedEntry) -> Option<String> {
+        let path = Path::new(&entry.path);
+        if entry.metadata.as_ref().is_some_and(|m| m.is_dir) {
+            return None;
+        }
+        path.extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+    }
+}
+
+impl FileSorter for ExtensionSorter {
+    fn compare(&self, a: &DecoratedEntry, b: &DecoratedEntry, _options: SortOptions) -> Ordering {
+        Self::extension(a).cmp(&Self::extension(b))
+    }
+}
@@ -0,0 +1,44 @@
+use super::{natural_cmp, FileSorter, SortOptions};
+use crate::filter::{leading_number, lookup_field};
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::cmp::Ordering;
+
+/// Sorts by a plugin custom field, e.g. `field:complexity`. Values that start
+/// with a number are compared numerically, others naturally; entries without
+/// the field come last.
+pub struct FieldSorter {
+    field: String,
+}
+
+impl FieldSorter {
+    pub fn new(field: String) -> Self {
+        Self { field }
+    }
+}
+
+impl FileSorter for FieldSorter {
+    fn compare(&self, a: &DecoratedEntry, b: &DecoratedEntry, _options: SortOptions) -> Ordering {
+        compare_values(
+            lookup_field(a, &self.field).as_deref(),
+            lookup_field(b, &self.field).as_deref(),
+        )
+    }
+
+    fn requires_decoration(&self) -> bool {
+        true
+    }
+}
+
+/// Compares field values numerically when both start with a number and
+/// naturally otherwise, putting missing values last.
+pub(crate) fn compare_values(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => match (leading_number(a), leading_number(b)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+            _ => natural_cmp(&a.to_lowercase(), &b.to_lowercase()),
+        },
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
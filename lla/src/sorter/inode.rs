@@ -0,0 +1,30 @@
+use super::{FileSorter, SortOptions};
+use lla_plugin_interface::proto::DecoratedEntry;
+use parking_lot::RwLock;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
+
+/// Sorts by inode number. Inodes aren't part of the entry metadata, so they
+/// are looked up once per entry and cached for the rest of the sort.
+#[derive(Default)]
+pub struct InodeSorter {
+    inodes: RwLock<HashMap<String, u64>>,
+}
+
+impl InodeSorter {
+    fn inode(&self, entry: &DecoratedEntry) -> u64 {
+        if let Some(inode) = self.inodes.read().get(&entry.path) {
+            return *inode;
+        }
+        let inode = std::fs::symlink_metadata(&entry.path).map_or(0, |m| m.ino());
+        self.inodes.write().insert(entry.path.clone(), inode);
+        inode
+    }
+}
+
+impl FileSorter for InodeSorter {
+    fn compare(&self, a: &DecoratedEntry, b: &DecoratedEntry, _options: SortOptions) -> Ordering {
+        self.inode(a).cmp(&self.inode(b))
+    }
+}
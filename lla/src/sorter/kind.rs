@@ -0,0 +1,24 @@
+use super::{FileSorter, SortOptions};
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::cmp::Ordering;
+
+/// Sorts by file kind: directories, symlinks, regular files, then everything
+/// else (sockets, pipes, devices).
+pub struct KindSorter;
+
+impl KindSorter {
+    fn rank(entry: &DecoratedEntry) -> u8 {
+        match &entry.metadata {
+            Some(m) if m.is_dir => 0,
+            Some(m) if m.is_symlink => 1,
+            Some(m) if m.is_file => 2,
+            _ => 3,
+        }
+    }
+}
+
+impl FileSorter for KindSorter {
+    fn compare(&self, a: &DecoratedEntry, b: &DecoratedEntry, _options: SortOptions) -> Ordering {
+        Self::rank(a).cmp(&Self::rank(b))
+    }
+}
@@ -1,5 +1,9 @@
 use crate::error::Result;
-use std::path::PathBuf;
+use crate::plugin::PluginManager;
+use lla_plugin_interface::proto::DecoratedEntry;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
 
 #[derive(Clone, Copy, Default)]
 pub struct SortOptions {
@@ -10,83 +14,219 @@ pub struct SortOptions {
 }
 
 pub trait FileSorter: Send + Sync {
+    /// Compares two entries by this sorter's key in its natural direction.
+    /// Directory grouping, tie-breaking by name and `reverse` are applied by
+    /// `sort_files_with_metadata`.
+    fn compare(&self, a: &DecoratedEntry, b: &DecoratedEntry, options: SortOptions) -> Ordering;
+
+    /// Whether `compare` puts larger values first, e.g. largest files first.
+    /// Used to resolve `:asc` and `:desc` in sort specs.
+    fn descending_by_default(&self) -> bool {
+        false
+    }
+
+    /// Whether the sort key comes from plugin decorations.
+    fn requires_decoration(&self) -> bool {
+        false
+    }
+
+    /// Fetches what `compare` needs from plugins before `entries` are sorted.
+    fn prepare(
+        &self,
+        _entries: &[DecoratedEntry],
+        _plugin_manager: &mut PluginManager,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     fn sort_files_with_metadata(
         &self,
         entries: &mut [(PathBuf, &DecoratedEntry)],
         options: SortOptions,
-    ) -> Result<()>;
+    ) -> Result<()> {
+        entries.par_sort_unstable_by(|(path_a, entry_a), (path_b, entry_b)| {
+            let order = compare_dirs_first(entry_a, entry_b, options.dirs_first)
+                .then_with(|| self.compare(entry_a, entry_b, options))
+                .then_with(|| compare_names(path_a, path_b, options));
+
+            if options.reverse {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+
+        Ok(())
+    }
 }
 
 mod alphabetical;
 mod date;
+mod extension;
+mod field;
+mod inode;
+mod kind;
+mod multi;
+mod owner;
+mod permissions;
+mod plugin;
 mod size;
+mod version;
 
 pub use alphabetical::AlphabeticalSorter;
-pub use date::DateSorter;
-use lla_plugin_interface::proto::DecoratedEntry;
+pub use date::{DateSorter, TimeKey};
+pub use extension::ExtensionSorter;
+pub use field::FieldSorter;
+pub use inode::InodeSorter;
+pub use kind::KindSorter;
+pub use multi::MultiSorter;
+pub use owner::{OwnerKey, OwnerSorter};
+pub use permissions::PermissionsSorter;
+pub use plugin::PluginSorter;
 pub use size::SizeSorter;
+pub(crate) use version::version_cmp;
+
+pub const SORT_KEYS: [&str; 12] = [
+    "name",
+    "version",
+    "size",
+    "date",
+    "accessed",
+    "created",
+    "extension",
+    "kind",
+    "owner",
+    "group",
+    "permissions",
+    "inode",
+];
+
+/// Returns whether `key` can name a sorter: one of [`SORT_KEYS`], a short
+/// alias, `field:<name>` for a plugin custom field or the name of a plugin
+/// sorter, which is only checked once plugins are loaded.
+pub fn is_sort_key(key: &str) -> bool {
+    let key = canonical_key(key);
+    SORT_KEYS.contains(&key)
+        || key.strip_prefix("field:").is_some_and(|f| !f.is_empty())
+        || is_plugin_sorter_name(key)
+}
+
+fn is_plugin_sorter_name(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// Splits a sort spec such as `ext,size:desc,name` into its keys and their
+/// optional directions.
+pub fn parse_sort_spec(spec: &str) -> Vec<(&str, Option<SortDirection>)> {
+    spec.split(',')
+        .map(str::trim)
+        .map(|part| match part.rsplit_once(':') {
+            Some((key, "asc")) => (key, Some(SortDirection::Asc)),
+            Some((key, "desc")) => (key, Some(SortDirection::Desc)),
+            _ => (part, None),
+        })
+        .collect()
+}
+
+/// Returns whether every key of a sort spec names a sorter.
+pub fn is_sort_spec(spec: &str) -> bool {
+    parse_sort_spec(spec)
+        .iter()
+        .all(|(key, _)| is_sort_key(key))
+}
 
-pub(crate) fn compare_dirs_first(a: &PathBuf, b: &PathBuf, dirs_first: bool) -> std::cmp::Ordering {
+/// Maps short aliases such as `ext` or `perm` to their canonical sort key.
+pub fn canonical_key(key: &str) -> &str {
+    match key {
+        "modified" | "time" => "date",
+        "ext" => "extension",
+        "type" => "kind",
+        "user" => "owner",
+        "perm" | "perms" => "permissions",
+        other => other,
+    }
+}
+
+pub(crate) fn compare_dirs_first(
+    a: &DecoratedEntry,
+    b: &DecoratedEntry,
+    dirs_first: bool,
+) -> Ordering {
     if !dirs_first {
-        return std::cmp::Ordering::Equal;
+        return Ordering::Equal;
     }
 
-    let a_is_dir = if let Ok(metadata) = a.symlink_metadata() {
-        metadata.is_dir()
-    } else {
-        a.is_dir()
-    };
+    let a_is_dir = a.metadata.as_ref().is_some_and(|m| m.is_dir);
+    let b_is_dir = b.metadata.as_ref().is_some_and(|m| m.is_dir);
+    b_is_dir.cmp(&a_is_dir)
+}
 
-    let b_is_dir = if let Ok(metadata) = b.symlink_metadata() {
-        metadata.is_dir()
-    } else {
-        b.is_dir()
-    };
+pub(crate) fn compare_names(a: &Path, b: &Path, options: SortOptions) -> Ordering {
+    let a_name = a.file_name().unwrap_or_default().to_string_lossy();
+    let b_name = b.file_name().unwrap_or_default().to_string_lossy();
 
-    match (a_is_dir, b_is_dir) {
-        (true, false) => std::cmp::Ordering::Less,
-        (false, true) => std::cmp::Ordering::Greater,
-        _ => std::cmp::Ordering::Equal,
+    if options.natural {
+        natural_cmp(&a_name, &b_name)
+    } else if options.case_sensitive {
+        a_name.cmp(&b_name)
+    } else {
+        a_name.to_lowercase().cmp(&b_name.to_lowercase())
     }
 }
 
-pub(crate) fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+pub(crate) fn natural_cmp(a: &str, b: &str) -> Ordering {
     let mut a_chars = a.chars().peekable();
     let mut b_chars = b.chars().peekable();
 
-    while a_chars.peek().is_some() && b_chars.peek().is_some() {
-        if a_chars.peek().unwrap().is_ascii_digit() && b_chars.peek().unwrap().is_ascii_digit() {
-            let mut a_num = 0u64;
-            let mut b_num = 0u64;
-
-            while a_chars.peek().map_or(false, |c| c.is_ascii_digit()) {
-                if let Some(digit) = a_chars.next() {
-                    a_num = a_num * 10 + digit.to_digit(10).unwrap() as u64;
-                }
-            }
-
-            while b_chars.peek().map_or(false, |c| c.is_ascii_digit()) {
-                if let Some(digit) = b_chars.next() {
-                    b_num = b_num * 10 + digit.to_digit(10).unwrap() as u64;
-                }
-            }
+    while let (Some(&a_char), Some(&b_char)) = (a_chars.peek(), b_chars.peek()) {
+        if a_char.is_ascii_digit() && b_char.is_ascii_digit() {
+            // Digit runs are compared as strings so arbitrarily long numbers
+            // can't overflow: strip leading zeros, then the longer run is the
+            // larger number.
+            let a_num = take_digits(&mut a_chars);
+            let b_num = take_digits(&mut b_chars);
+            let a_trimmed = a_num.trim_start_matches('0');
+            let b_trimmed = b_num.trim_start_matches('0');
 
-            match a_num.cmp(&b_num) {
-                std::cmp::Ordering::Equal => continue,
-                other => return other,
+            let order = a_trimmed
+                .len()
+                .cmp(&b_trimmed.len())
+                .then_with(|| a_trimmed.cmp(b_trimmed))
+                .then_with(|| b_num.len().cmp(&a_num.len()));
+            if order.is_ne() {
+                return order;
             }
         } else {
-            match a_chars.next().unwrap().cmp(&b_chars.next().unwrap()) {
-                std::cmp::Ordering::Equal => continue,
+            a_chars.next();
+            b_chars.next();
+            match a_char.cmp(&b_char) {
+                Ordering::Equal => continue,
                 other => return other,
             }
         }
     }
 
     match (a_chars.peek(), b_chars.peek()) {
-        (None, None) => std::cmp::Ordering::Equal,
-        (None, Some(_)) => std::cmp::Ordering::Less,
-        (Some(_), None) => std::cmp::Ordering::Greater,
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
         _ => unreachable!(),
     }
 }
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(digit);
+    }
+    digits
+}
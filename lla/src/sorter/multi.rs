@@ -0,0 +1,54 @@
+use super::{FileSorter, SortOptions};
+use crate::error::Result;
+use crate::plugin::PluginManager;
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// Chains several sorters, e.g. for `--sort ext,size:desc,name`. Later keys
+/// only break ties left by earlier ones.
+pub struct MultiSorter {
+    keys: Vec<(Arc<dyn FileSorter + Send + Sync>, bool)>,
+}
+
+impl MultiSorter {
+    /// Creates a sorter from `(sorter, reverse)` pairs, where `reverse` flips
+    /// that key's direction.
+    pub fn new(keys: Vec<(Arc<dyn FileSorter + Send + Sync>, bool)>) -> Self {
+        Self { keys }
+    }
+}
+
+impl FileSorter for MultiSorter {
+    fn compare(&self, a: &DecoratedEntry, b: &DecoratedEntry, options: SortOptions) -> Ordering {
+        self.keys
+            .iter()
+            .map(|(sorter, reverse)| {
+                let order = sorter.compare(a, b, options);
+                if *reverse {
+                    order.reverse()
+                } else {
+                    order
+                }
+            })
+            .find(|order| order.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    fn requires_decoration(&self) -> bool {
+        self.keys
+            .iter()
+            .any(|(sorter, _)| sorter.requires_decoration())
+    }
+
+    fn prepare(
+        &self,
+        entries: &[DecoratedEntry],
+        plugin_manager: &mut PluginManager,
+    ) -> Result<()> {
+        for (sorter, _) in &self.keys {
+            sorter.prepare(entries, plugin_manager)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use super::{FileSorter, SortOptions};
+use lla_plugin_interface::proto::DecoratedEntry;
+use parking_lot::RwLock;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use users::{get_group_by_gid, get_user_by_uid};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OwnerKey {
+    User,
+    Group,
+}
+
+/// Sorts by owning user or group name, falling back to the numeric id for
+/// ids without a name. Names are resolved once per id.
+pub struct OwnerSorter {
+    key: OwnerKey,
+    names: RwLock<HashMap<u32, String>>,
+}
+
+impl OwnerSorter {
+    pub fn new(key: OwnerKey) -> Self {
+        Self {
+            key,
+            names: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn name(&self, entry: &DecoratedEntry) -> String {
+        let id = match (&entry.metadata, self.key) {
+            (Some(m), OwnerKey::User) => m.uid,
+            (Some(m), OwnerKey::Group) => m.gid,
+            (None, _) => return String::new(),
+        };
+
+        if let Some(name) = self.names.read().get(&id) {
+            return name.clone();
+        }
+
+        let name = match self.key {
+            OwnerKey::User => get_user_by_uid(id).map(|u| u.name().to_string_lossy().into_owned()),
+            OwnerKey::Group => {
+                get_group_by_gid(id).map(|g| g.name().to_string_lossy().into_owned())
+            }
+        }
+        .unwrap_or_else(|| id.to_string());
+        self.names.write().insert(id, name.clone());
+        name
+    }
+}
+
+impl FileSorter for OwnerSorter {
+    fn compare(&self, a: &DecoratedEntry, b: &DecoratedEntry, _options: SortOptions) -> Ordering {
+        self.name(a).cmp(&self.name(b))
+    }
+}
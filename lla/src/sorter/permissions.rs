@@ -0,0 +1,14 @@
+use super::{FileSorter, SortOptions};
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::cmp::Ordering;
+
+/// Sorts by permission bits, most restrictive mode first.
+pub struct PermissionsSorter;
+
+impl FileSorter for PermissionsSorter {
+    fn compare(&self, a: &DecoratedEntry, b: &DecoratedEntry, _options: SortOptions) -> Ordering {
+        let mode_a = a.metadata.as_ref().map_or(0, |m| m.permissions & 0o7777);
+        let mode_b = b.metadata.as_ref().map_or(0, |m| m.permissions & 0o7777);
+        mode_a.cmp(&mode_b)
+    }
+}
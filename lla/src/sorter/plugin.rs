@@ -0,0 +1,52 @@
+use super::field::compare_values;
+use super::{FileSorter, SortOptions};
+use crate::error::Result;
+use crate::plugin::PluginManager;
+use lla_plugin_interface::proto::DecoratedEntry;
+use parking_lot::RwLock;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Sorts by keys a plugin computes for a whole listing, e.g. `--sort complexity`.
+/// Keys are fetched in `prepare` and compared like `field:` values; entries
+/// without a key come last.
+pub struct PluginSorter {
+    plugin: String,
+    sorter: String,
+    keys: RwLock<HashMap<String, String>>,
+}
+
+impl PluginSorter {
+    pub fn new(plugin: String, sorter: String) -> Self {
+        Self {
+            plugin,
+            sorter,
+            keys: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl FileSorter for PluginSorter {
+    fn compare(&self, a: &DecoratedEntry, b: &DecoratedEntry, _options: SortOptions) -> Ordering {
+        let keys = self.keys.read();
+        compare_values(
+            keys.get(&a.path).map(String::as_str),
+            keys.get(&b.path).map(String::as_str),
+        )
+    }
+
+    fn prepare(
+        &self,
+        entries: &[DecoratedEntry],
+        plugin_manager: &mut PluginManager,
+    ) -> Result<()> {
+        let keys = plugin_manager.sort_keys(&self.plugin, &self.sorter, entries)?;
+        *self.keys.write() = entries
+            .iter()
+            .zip(keys)
+            .filter(|(_, key)| !key.is_empty())
+            .map(|(entry, key)| (entry.path.clone(), key))
+            .collect();
+        Ok(())
+    }
+}
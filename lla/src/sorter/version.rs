@@ -0,0 +1,72 @@
+use std::cmp::Ordering;
+
+/// Compares version strings the way Debian's `dpkg` and `ls -v` do:
+/// digit runs compare numerically (ignoring leading zeros), letters sort
+/// before other characters, and a `~` sorts before anything, even the end of
+/// the string.
+///
+/// As in semver, a `-` between a digit and a letter starts a pre-release, so
+/// `1.10.0-rc1` sorts before `1.10.0` but after `1.9.2`.
+pub(crate) fn version_cmp(a: &str, b: &str) -> Ordering {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let order = weight(&a, i).cmp(&weight(&b, j));
+            if order.is_ne() {
+                return order;
+            }
+            i += 1;
+            j += 1;
+        }
+
+        while i < a.len() && a[i] == '0' {
+            i += 1;
+        }
+        while j < b.len() && b[j] == '0' {
+            j += 1;
+        }
+
+        let mut first_diff = Ordering::Equal;
+        while i < a.len() && j < b.len() && a[i].is_ascii_digit() && b[j].is_ascii_digit() {
+            if first_diff.is_eq() {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+
+        if i < a.len() && a[i].is_ascii_digit() {
+            return Ordering::Greater;
+        }
+        if j < b.len() && b[j].is_ascii_digit() {
+            return Ordering::Less;
+        }
+        if first_diff.is_ne() {
+            return first_diff;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Sort weight of the character at `index`; the end of the string and digits
+/// weigh 0 so that a shorter non-digit run sorts first.
+fn weight(chars: &[char], index: usize) -> i64 {
+    match chars.get(index) {
+        None => 0,
+        Some(c) if c.is_ascii_digit() => 0,
+        Some('~') => -1,
+        Some('-') if is_pre_release(chars, index) => -1,
+        Some(c) if c.is_alphabetic() => *c as i64,
+        Some(c) => *c as i64 + 0x110000,
+    }
+}
+
+fn is_pre_release(chars: &[char], index: usize) -> bool {
+    index > 0
+        && chars[index - 1].is_ascii_digit()
+        && chars.get(index + 1).is_some_and(|c| c.is_alphabetic())
+}
@@ -100,6 +100,14 @@ pub struct ThemeColors {
     pub permission_exec: ColorValue,
     #[serde(default = "default_permission_none_color")]
     pub permission_none: ColorValue,
+    #[serde(default = "default_git_staged_color")]
+    pub git_staged: ColorValue,
+    #[serde(default = "default_git_modified_color")]
+    pub git_modified: ColorValue,
+    #[serde(default = "default_git_untracked_color")]
+    pub git_untracked: ColorValue,
+    #[serde(default = "default_git_clean_color")]
+    pub git_clean: ColorValue,
 }
 
 impl Default for Theme {
@@ -131,6 +139,10 @@ impl Default for ThemeColors {
             permission_write: default_permission_write_color(),
             permission_exec: default_permission_exec_color(),
             permission_none: default_permission_none_color(),
+            git_staged: default_git_staged_color(),
+            git_modified: default_git_modified_color(),
+            git_untracked: default_git_untracked_color(),
+            git_clean: default_git_clean_color(),
         }
     }
 }
@@ -174,6 +186,18 @@ fn default_permission_exec_color() -> ColorValue {
 fn default_permission_none_color() -> ColorValue {
     ColorValue::Named("bright_black".to_string())
 }
+fn default_git_staged_color() -> ColorValue {
+    ColorValue::Named("green".to_string())
+}
+fn default_git_modified_color() -> ColorValue {
+    ColorValue::Named("red".to_string())
+}
+fn default_git_untracked_color() -> ColorValue {
+    ColorValue::Named("red".to_string())
+}
+fn default_git_clean_color() -> ColorValue {
+    ColorValue::Named("bright_black".to_string())
+}
 
 pub fn color_value_to_color(color_value: &ColorValue) -> Color {
     if is_no_color() {
@@ -205,6 +229,46 @@ pub fn color_value_to_color(color_value: &ColorValue) -> Color {
     }
 }
 
+/// CSS color for a theme color, used by the HTML report. Unlike
+/// [`color_value_to_color`] this ignores `--no-color`; terminal color names
+/// map to the xterm palette.
+pub fn color_value_to_css(color_value: &ColorValue) -> String {
+    let (r, g, b) = match color_value {
+        ColorValue::None => return "inherit".to_string(),
+        ColorValue::Named(name) => return color_to_css(str_to_color(name)),
+        ColorValue::RGB { r, g, b } | ColorValue::RGBA { r, g, b, .. } => (*r, *g, *b),
+        ColorValue::HSL { h, s, l } => hsl_to_rgb(*h, *s, *l),
+        ColorValue::Hex(hex) => match parse_hex_color(hex) {
+            Some(rgb) => rgb,
+            None => return "inherit".to_string(),
+        },
+    };
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+pub fn color_to_css(color: Color) -> String {
+    let hex = match color {
+        Color::Black => "#000000",
+        Color::Red => "#cd0000",
+        Color::Green => "#00cd00",
+        Color::Yellow => "#cdcd00",
+        Color::Blue => "#0000ee",
+        Color::Magenta => "#cd00cd",
+        Color::Cyan => "#00cdcd",
+        Color::White => "#e5e5e5",
+        Color::BrightBlack => "#7f7f7f",
+        Color::BrightRed => "#ff0000",
+        Color::BrightGreen => "#00ff00",
+        Color::BrightYellow => "#ffff00",
+        Color::BrightBlue => "#5c5cff",
+        Color::BrightMagenta => "#ff00ff",
+        Color::BrightCyan => "#00ffff",
+        Color::BrightWhite => "#ffffff",
+        Color::TrueColor { r, g, b } => return format!("#{:02x}{:02x}{:02x}", r, g, b),
+    };
+    hex.to_string()
+}
+
 fn hex_to_color(hex: &str) -> Color {
     if let Some((r, g, b)) = parse_hex_color(hex) {
         Color::TrueColor { r, g, b }
@@ -1,5 +1,7 @@
 use crate::commands::args::Args;
 use crate::theme::{color_value_to_color, get_file_color, is_no_color, ColorValue, Theme};
+use crate::utils::size::format_size;
+use crate::utils::time::get_time_style;
 use colored::*;
 use std::path::Path;
 use std::sync::OnceLock;
@@ -103,15 +105,7 @@ pub fn colorize_file_name_with_icon(path: &Path, content: String) -> ColoredStri
 }
 
 pub fn colorize_size(size: u64) -> ColoredString {
-    let formatted = if size < 1024 {
-        format!("{}B", size)
-    } else if size < 1024 * 1024 {
-        format!("{:.1}K", size as f64 / 1024.0)
-    } else if size < 1024 * 1024 * 1024 {
-        format!("{:.1}M", size as f64 / (1024.0 * 1024.0))
-    } else {
-        format!("{:.1}G", size as f64 / (1024.0 * 1024.0 * 1024.0))
-    };
+    let formatted = format_size(size);
 
     if is_no_color() {
         formatted.normal()
@@ -504,8 +498,10 @@ fn triplet_no_color(mode: u32, _shift: u32) -> String {
 }
 
 pub fn colorize_date(date: &std::time::SystemTime) -> ColoredString {
-    let datetime: chrono::DateTime<chrono::Local> = (*date).into();
-    let formatted = datetime.format("%b %d %H:%M").to_string();
+    let formatted = get_time_style().format(*date).unwrap_or_else(|| {
+        let datetime: chrono::DateTime<chrono::Local> = (*date).into();
+        datetime.format("%b %d %H:%M").to_string()
+    });
 
     if is_no_color() {
         formatted.normal()
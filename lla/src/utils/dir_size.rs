@@ -0,0 +1,144 @@
+use super::size::{allocated_size, get_size_style};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, Metadata};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Total sizes of every directory below a root, computed in a single
+/// bottom-up walk so nested directories are never traversed twice.
+///
+/// Directories are keyed by `(dev, ino)`, which makes lookups independent of
+/// how a lister spelled the path. Files with several hard links are counted
+/// once, at the first link the walk reaches.
+pub struct DirSizes {
+    sizes: HashMap<(u64, u64), DirSize>,
+}
+
+/// Total size of everything below a directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DirSize {
+    pub apparent: u64,
+    /// Space allocated on disk, including the directories themselves.
+    pub allocated: u64,
+}
+
+impl DirSize {
+    /// The total shown, depending on `--disk-usage`.
+    pub fn shown(&self) -> u64 {
+        if get_size_style().disk_usage {
+            self.allocated
+        } else {
+            self.apparent
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            apparent: self.apparent + other.apparent,
+            allocated: self.allocated + other.allocated,
+        }
+    }
+}
+
+struct Walk {
+    root_dev: u64,
+    one_file_system: bool,
+    seen_links: Mutex<HashSet<(u64, u64)>>,
+    sizes: Mutex<HashMap<(u64, u64), DirSize>>,
+}
+
+impl DirSizes {
+    /// Walks `root` once. With `one_file_system`, directories on other
+    /// filesystems than `root` are neither descended into nor counted.
+    pub fn compute(root: &Path, one_file_system: bool) -> Self {
+        let Ok(metadata) = fs::metadata(root) else {
+            return Self {
+                sizes: HashMap::new(),
+            };
+        };
+
+        let walk = Walk {
+            root_dev: metadata.dev(),
+            one_file_system,
+            seen_links: Mutex::new(HashSet::new()),
+            sizes: Mutex::new(HashMap::new()),
+        };
+        if metadata.is_dir() {
+            walk.visit(root, &metadata);
+        }
+
+        Self {
+            sizes: walk.sizes.into_inner().unwrap_or_default(),
+        }
+    }
+
+    pub fn get(&self, metadata: &Metadata) -> Option<DirSize> {
+        self.sizes.get(&(metadata.dev(), metadata.ino())).copied()
+    }
+
+    /// Lowers the shown total of a directory, e.g. after deleting something
+    /// in it.
+    pub fn shrink(&mut self, metadata: &Metadata, bytes: u64) {
+        if let Some(size) = self.sizes.get_mut(&(metadata.dev(), metadata.ino())) {
+            let shown = if get_size_style().disk_usage {
+                &mut size.allocated
+            } else {
+                &mut size.apparent
+            };
+            *shown = shown.saturating_sub(bytes);
+        }
+    }
+}
+
+impl Walk {
+    fn visit(&self, dir: &Path, metadata: &Metadata) -> DirSize {
+        let mut total = DirSize {
+            apparent: 0,
+            allocated: allocated_size(metadata),
+        };
+
+        let mut subdirs: Vec<(PathBuf, Metadata)> = Vec::new();
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if metadata.is_symlink() {
+                    continue;
+                }
+                if metadata.is_dir() {
+                    if !self.one_file_system || metadata.dev() == self.root_dev {
+                        subdirs.push((entry.path(), metadata));
+                    }
+                } else if self.first_link(&metadata) {
+                    total.apparent += metadata.len();
+                    total.allocated += allocated_size(&metadata);
+                }
+            }
+        }
+
+        let total = total.add(
+            subdirs
+                .par_iter()
+                .map(|(path, metadata)| self.visit(path, metadata))
+                .reduce(DirSize::default, DirSize::add),
+        );
+
+        self.sizes
+            .lock()
+            .unwrap()
+            .insert((metadata.dev(), metadata.ino()), total);
+        total
+    }
+
+    fn first_link(&self, metadata: &Metadata) -> bool {
+        metadata.nlink() <= 1
+            || self
+                .seen_links
+                .lock()
+                .unwrap()
+                .insert((metadata.dev(), metadata.ino()))
+    }
+}
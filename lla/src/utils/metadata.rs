@@ -0,0 +1,27 @@
+use lla_plugin_interface::proto::EntryMetadata;
+use std::os::unix::fs::MetadataExt;
+use std::time::UNIX_EPOCH;
+
+pub fn convert_metadata(metadata: &std::fs::Metadata) -> EntryMetadata {
+    EntryMetadata {
+        size: metadata.len(),
+        modified: metadata
+            .modified()
+            .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
+            .unwrap_or(0),
+        accessed: metadata
+            .accessed()
+            .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
+            .unwrap_or(0),
+        created: metadata
+            .created()
+            .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
+            .unwrap_or(0),
+        is_dir: metadata.is_dir(),
+        is_file: metadata.is_file(),
+        is_symlink: metadata.is_symlink(),
+        permissions: metadata.mode(),
+        uid: metadata.uid(),
+        gid: metadata.gid(),
+    }
+}
@@ -1,2 +1,6 @@
 pub mod color;
+pub mod dir_size;
 pub mod icons;
+pub mod metadata;
+pub mod size;
+pub mod time;
@@ -0,0 +1,134 @@
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::fs::{self, Metadata};
+use std::os::unix::fs::MetadataExt;
+use std::sync::OnceLock;
+
+static CURRENT_STYLE: OnceLock<SizeStyle> = OnceLock::new();
+
+/// Block size used by `--size-format blocks`, matching `ls -s` and `du`.
+const BLOCK_SIZE: u64 = 1024;
+/// Unit of `st_blocks`, independent of the filesystem block size.
+const ST_BLOCK_SIZE: u64 = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SizeFormat {
+    /// Powers of 1024: `1.5K`, `20.0M`.
+    #[default]
+    Binary,
+    /// Powers of 1000: `1.5k`, `20.0M`.
+    Si,
+    /// Exact byte count.
+    Bytes,
+    /// Number of 1 KiB blocks.
+    Blocks,
+}
+
+impl SizeFormat {
+    pub const NAMES: &'static [&'static str] = &["binary", "si", "bytes", "blocks"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "binary" | "iec" => Some(SizeFormat::Binary),
+            "si" => Some(SizeFormat::Si),
+            "bytes" => Some(SizeFormat::Bytes),
+            "blocks" => Some(SizeFormat::Blocks),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeStyle {
+    pub format: SizeFormat,
+    /// Decimal places for scaled units.
+    pub precision: usize,
+    /// Report allocated disk usage (`st_blocks`) instead of the apparent size.
+    pub disk_usage: bool,
+}
+
+impl Default for SizeStyle {
+    fn default() -> Self {
+        Self {
+            format: SizeFormat::Binary,
+            precision: 1,
+            disk_usage: false,
+        }
+    }
+}
+
+pub fn set_size_style(style: SizeStyle) {
+    let _ = CURRENT_STYLE.set(style);
+}
+
+pub fn get_size_style() -> &'static SizeStyle {
+    CURRENT_STYLE.get_or_init(SizeStyle::default)
+}
+
+/// Custom field holding the space allocated for the contents of a directory
+/// whose total size is listed.
+pub const ALLOCATED_SIZE_FIELD: &str = "allocated_size";
+
+/// The size of a file as shown: either its apparent length or the space
+/// allocated for it on disk.
+pub fn entry_size(metadata: &Metadata) -> u64 {
+    if get_size_style().disk_usage {
+        allocated_size(metadata)
+    } else {
+        metadata.len()
+    }
+}
+
+pub fn allocated_size(metadata: &Metadata) -> u64 {
+    metadata.blocks() * ST_BLOCK_SIZE
+}
+
+/// The size shown for a listed entry. Its metadata always holds the apparent
+/// size, which sorting, filters, plugins and machine output use; with
+/// `--disk-usage` the allocated size is looked up when the entry is shown.
+pub fn shown_size(entry: &DecoratedEntry) -> u64 {
+    let apparent = entry.metadata.as_ref().map_or(0, |m| m.size);
+    if !get_size_style().disk_usage {
+        return apparent;
+    }
+    if let Some(total) = allocated_total(entry) {
+        return total;
+    }
+    fs::symlink_metadata(&entry.path).map_or(apparent, |m| allocated_size(&m))
+}
+
+pub fn format_size(bytes: u64) -> String {
+    format_size_with(bytes, get_size_style())
+}
+
+pub fn format_size_with(bytes: u64, style: &SizeStyle) -> String {
+    match style.format {
+        SizeFormat::Binary => scale(bytes, 1024.0, &["K", "M", "G", "T", "P", "E"], style),
+        SizeFormat::Si => scale(bytes, 1000.0, &["k", "M", "G", "T", "P", "E"], style),
+        SizeFormat::Bytes => bytes.to_string(),
+        SizeFormat::Blocks => bytes.div_ceil(BLOCK_SIZE).to_string(),
+    }
+}
+
+fn scale(bytes: u64, base: f64, units: &[&str], style: &SizeStyle) -> String {
+    let mut value = bytes as f64;
+    if value < base {
+        return format!("{}B", bytes);
+    }
+
+    let mut unit = 0;
+    value /= base;
+    while value >= base && unit + 1 < units.len() {
+        value /= base;
+        unit += 1;
+    }
+    format!("{:.*}{}", style.precision, value, units[unit])
+}
+
+/// The allocated size of the contents of a directory entry listed with its
+/// total size.
+pub fn allocated_total(entry: &DecoratedEntry) -> Option<u64> {
+    entry
+        .custom_fields
+        .get(ALLOCATED_SIZE_FIELD)
+        .and_then(|total| total.parse().ok())
+}
@@ -0,0 +1,134 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, Months};
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static CURRENT_STYLE: OnceLock<TimeStyle> = OnceLock::new();
+
+/// How timestamps are rendered in human-readable views.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum TimeStyle {
+    /// Each view's built-in format.
+    #[default]
+    Default,
+    /// `10-21 14:05` for recent files, `2023-10-21` for older ones.
+    Iso,
+    /// `2024-10-21 14:05`.
+    LongIso,
+    /// `2024-10-21 14:05:09 +0200`.
+    FullIso,
+    /// `2 hours ago`.
+    Relative,
+    /// A strftime format, written as `+FORMAT` or any string containing `%`.
+    Custom(String),
+}
+
+pub const TIME_STYLES: &[&str] = &["default", "iso", "long-iso", "full-iso", "relative"];
+
+impl TimeStyle {
+    pub fn parse(style: &str) -> Option<Self> {
+        let style = match style {
+            "default" => TimeStyle::Default,
+            "iso" => TimeStyle::Iso,
+            "long-iso" => TimeStyle::LongIso,
+            "full-iso" => TimeStyle::FullIso,
+            "relative" => TimeStyle::Relative,
+            _ => {
+                let format = style.strip_prefix('+').unwrap_or(style);
+                if !style.starts_with('+') && !format.contains('%') {
+                    return None;
+                }
+                if StrftimeItems::new(format).any(|item| item == Item::Error) {
+                    return None;
+                }
+                TimeStyle::Custom(format.to_string())
+            }
+        };
+        Some(style)
+    }
+
+    /// Formats `time`, or returns `None` for [`TimeStyle::Default`] so the
+    /// caller can fall back to its own format.
+    pub fn format(&self, time: SystemTime) -> Option<String> {
+        let dt: DateTime<Local> = time.into();
+        let text = match self {
+            TimeStyle::Default => return None,
+            TimeStyle::Iso => {
+                let six_months_ago = Local::now()
+                    .checked_sub_months(Months::new(6))
+                    .unwrap_or(dt);
+                if dt > six_months_ago {
+                    dt.format("%m-%d %H:%M").to_string()
+                } else {
+                    dt.format("%Y-%m-%d").to_string()
+                }
+            }
+            TimeStyle::LongIso => dt.format("%Y-%m-%d %H:%M").to_string(),
+            TimeStyle::FullIso => dt.format("%Y-%m-%d %H:%M:%S %z").to_string(),
+            TimeStyle::Relative => {
+                let delta = dt.signed_duration_since(Local::now());
+                chrono_humanize::HumanTime::from(delta).to_string()
+            }
+            TimeStyle::Custom(format) => dt.format(format).to_string(),
+        };
+        Some(text)
+    }
+}
+
+pub fn set_time_style(style: TimeStyle) {
+    let _ = CURRENT_STYLE.set(style);
+}
+
+pub fn get_time_style() -> &'static TimeStyle {
+    CURRENT_STYLE.get_or_init(TimeStyle::default)
+}
+
+/// Which timestamp of an entry the views show and group by.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TimeField {
+    #[default]
+    Modified,
+    Accessed,
+    Created,
+    /// Inode change time (`ctime`).
+    Changed,
+}
+
+impl TimeField {
+    pub const NAMES: &'static [&'static str] = &["modified", "accessed", "created", "changed"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "modified" | "mtime" => Some(TimeField::Modified),
+            "accessed" | "atime" => Some(TimeField::Accessed),
+            "created" | "btime" => Some(TimeField::Created),
+            "changed" | "ctime" => Some(TimeField::Changed),
+            _ => None,
+        }
+    }
+
+    /// Seconds since the epoch of this timestamp for `entry`. The change time
+    /// isn't part of the entry metadata and is read from the filesystem.
+    pub fn secs(&self, entry: &DecoratedEntry) -> u64 {
+        let metadata = entry.metadata.as_ref().cloned().unwrap_or_default();
+        match self {
+            TimeField::Modified => metadata.modified,
+            TimeField::Accessed => metadata.accessed,
+            TimeField::Created => metadata.created,
+            TimeField::Changed => changed_secs(&entry.path).unwrap_or(0),
+        }
+    }
+
+    pub fn time(&self, entry: &DecoratedEntry) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.secs(entry))
+    }
+}
+
+pub fn changed_secs(path: &str) -> Option<u64> {
+    fs::symlink_metadata(path)
+        .ok()
+        .and_then(|m| u64::try_from(m.ctime()).ok())
+}
@@ -39,7 +39,7 @@ pub struct DecoratedEntry {
 pub struct PluginMessage {
     #[prost(
         oneof = "plugin_message::Message",
-        tags = "1, 2, 3, 4, 5, 6, 7, 101, 102, 103, 104, 105, 106, 107, 108"
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116"
     )]
     pub message: ::core::option::Option<plugin_message::Message>,
 }
@@ -62,6 +62,22 @@ pub mod plugin_message {
         FormatField(super::FormatFieldRequest),
         #[prost(message, tag = "7")]
         Action(super::ActionRequest),
+        #[prost(message, tag = "8")]
+        DecorateBatch(super::DecorateBatch),
+        #[prost(message, tag = "9")]
+        Handshake(super::Handshake),
+        #[prost(bool, tag = "10")]
+        GetFormatters(bool),
+        #[prost(message, tag = "11")]
+        FormatEntries(super::FormatEntriesRequest),
+        #[prost(bool, tag = "12")]
+        GetSorters(bool),
+        #[prost(bool, tag = "13")]
+        GetFilters(bool),
+        #[prost(message, tag = "14")]
+        SortKeys(super::SortKeysRequest),
+        #[prost(message, tag = "15")]
+        FilterEntries(super::FilterEntriesRequest),
         #[prost(string, tag = "101")]
         NameResponse(::prost::alloc::string::String),
         #[prost(string, tag = "102")]
@@ -78,10 +94,42 @@ pub mod plugin_message {
         ActionResponse(super::ActionResponse),
         #[prost(string, tag = "108")]
         ErrorResponse(::prost::alloc::string::String),
+        #[prost(message, tag = "109")]
+        DecoratedBatchResponse(super::DecorateBatch),
+        #[prost(message, tag = "110")]
+        HandshakeResponse(super::Handshake),
+        #[prost(message, tag = "111")]
+        FormattersResponse(super::FormattersResponse),
+        #[prost(string, tag = "112")]
+        FormattedEntriesResponse(::prost::alloc::string::String),
+        #[prost(message, tag = "113")]
+        SortersResponse(super::SortersResponse),
+        #[prost(message, tag = "114")]
+        FiltersResponse(super::FiltersResponse),
+        #[prost(message, tag = "115")]
+        SortKeysResponse(super::SortKeysResponse),
+        #[prost(message, tag = "116")]
+        FilterEntriesResponse(super::FilterEntriesResponse),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Handshake {
+    #[prost(uint32, tag = "1")]
+    pub min_api_version: u32,
+    #[prost(uint32, tag = "2")]
+    pub max_api_version: u32,
+    #[prost(string, repeated, tag = "3")]
+    pub capabilities: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecorateBatch {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: ::prost::alloc::vec::Vec<DecoratedEntry>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FormatFieldRequest {
     #[prost(message, optional, tag = "1")]
     pub entry: ::core::option::Option<DecoratedEntry>,
@@ -90,6 +138,32 @@ pub struct FormatFieldRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FormatEntriesRequest {
+    #[prost(string, tag = "1")]
+    pub formatter: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub entries: ::prost::alloc::vec::Vec<DecoratedEntry>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SortKeysRequest {
+    #[prost(string, tag = "1")]
+    pub sorter: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub entries: ::prost::alloc::vec::Vec<DecoratedEntry>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterEntriesRequest {
+    #[prost(string, tag = "1")]
+    pub filter: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub argument: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub entries: ::prost::alloc::vec::Vec<DecoratedEntry>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ActionRequest {
     #[prost(string, tag = "1")]
     pub action: ::prost::alloc::string::String,
@@ -104,6 +178,36 @@ pub struct SupportedFormatsResponse {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FormattersResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub formatters: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SortersResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub sorters: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FiltersResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub filters: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SortKeysResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub keys: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterEntriesResponse {
+    #[prost(bool, repeated, tag = "1")]
+    pub matches: ::prost::alloc::vec::Vec<bool>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FormattedFieldResponse {
     #[prost(string, optional, tag = "1")]
     pub field: ::core::option::Option<::prost::alloc::string::String>,
@@ -35,29 +35,125 @@ pub struct EntryMetadata {
     pub gid: u32,
 }
 
+/// The API versions and optional features a host or plugin supports,
+/// exchanged when a plugin is loaded.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Handshake {
+    pub min_api_version: u32,
+    pub max_api_version: u32,
+    pub capabilities: Vec<String>,
+}
+
+impl Handshake {
+    /// The API versions of this crate with the given capabilities.
+    pub fn current(capabilities: &[&str]) -> Self {
+        Handshake {
+            min_api_version: MIN_PLUGIN_API_VERSION,
+            max_api_version: CURRENT_PLUGIN_API_VERSION,
+            capabilities: capabilities.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+}
+
+/// Optional features announced in the handshake.
+pub mod capability {
+    /// Handles `DecorateBatch` requests.
+    pub const DECORATE_BATCH: &str = "decorate_batch";
+    /// Answers `GetFormatters` and renders listings for `--format <plugin>:<name>`.
+    pub const FORMATTERS: &str = "formatters";
+    /// Answers `GetSorters` and `SortKeys`, for `--sort <sorter>`.
+    pub const SORTERS: &str = "sorters";
+    /// Answers `GetFilters` and `FilterEntries`, for `--filter <filter>:<argument>`.
+    pub const FILTERS: &str = "filters";
+}
+
+/// A request to a plugin. New requests are added along with the capability
+/// announcing them, so plugins match the requests they handle and answer the
+/// rest with an error.
 #[derive(Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum PluginRequest {
     GetName,
     GetVersion,
     GetDescription,
     GetSupportedFormats,
+    Handshake(Handshake),
     Decorate(DecoratedEntry),
+    DecorateBatch(Vec<DecoratedEntry>),
     FormatField(DecoratedEntry, String),
+    GetFormatters,
+    FormatEntries(String, Vec<DecoratedEntry>),
+    GetSorters,
+    GetFilters,
+    SortKeys(String, Vec<DecoratedEntry>),
+    FilterEntries(String, String, Vec<DecoratedEntry>),
     PerformAction(String, Vec<String>),
 }
 
 #[derive(Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum PluginResponse {
     Name(String),
     Version(String),
     Description(String),
     SupportedFormats(Vec<String>),
+    Handshake(Handshake),
     Decorated(DecoratedEntry),
+    DecoratedBatch(Vec<DecoratedEntry>),
     FormattedField(Option<String>),
+    Formatters(Vec<String>),
+    FormattedEntries(String),
+    Sorters(Vec<String>),
+    Filters(Vec<String>),
+    SortKeys(Vec<String>),
+    FilterResults(Vec<bool>),
     ActionResult(Result<(), String>),
     Error(String),
 }
 
+impl PluginRequest {
+    /// Name of the request in error messages, e.g. `GetSorters`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PluginRequest::GetName => "GetName",
+            PluginRequest::GetVersion => "GetVersion",
+            PluginRequest::GetDescription => "GetDescription",
+            PluginRequest::GetSupportedFormats => "GetSupportedFormats",
+            PluginRequest::Handshake(_) => "Handshake",
+            PluginRequest::Decorate(_) => "Decorate",
+            PluginRequest::DecorateBatch(_) => "DecorateBatch",
+            PluginRequest::FormatField(..) => "FormatField",
+            PluginRequest::GetFormatters => "GetFormatters",
+            PluginRequest::FormatEntries(..) => "FormatEntries",
+            PluginRequest::GetSorters => "GetSorters",
+            PluginRequest::GetFilters => "GetFilters",
+            PluginRequest::SortKeys(..) => "SortKeys",
+            PluginRequest::FilterEntries(..) => "FilterEntries",
+            PluginRequest::PerformAction(..) => "PerformAction",
+        }
+    }
+}
+
+impl From<Handshake> for proto::Handshake {
+    fn from(handshake: Handshake) -> Self {
+        proto::Handshake {
+            min_api_version: handshake.min_api_version,
+            max_api_version: handshake.max_api_version,
+            capabilities: handshake.capabilities,
+        }
+    }
+}
+
+impl From<proto::Handshake> for Handshake {
+    fn from(handshake: proto::Handshake) -> Self {
+        Handshake {
+            min_api_version: handshake.min_api_version,
+            max_api_version: handshake.max_api_version,
+            capabilities: handshake.capabilities,
+        }
+    }
+}
+
 impl From<EntryMetadata> for proto::EntryMetadata {
     fn from(meta: EntryMetadata) -> Self {
         proto::EntryMetadata {
@@ -142,7 +238,12 @@ pub struct PluginApi {
     pub free_response: extern "C" fn(*mut RawBuffer),
 }
 
-pub const CURRENT_PLUGIN_API_VERSION: u32 = 1;
+/// API version plugins built against this crate report. Version 2 added the
+/// handshake and batch decoration.
+pub const CURRENT_PLUGIN_API_VERSION: u32 = 2;
+
+/// Oldest API version hosts built against this crate still load.
+pub const MIN_PLUGIN_API_VERSION: u32 = 1;
 
 #[repr(C)]
 pub struct PluginContext(*mut std::ffi::c_void);
@@ -0,0 +1,503 @@
+use chrono::{TimeZone, Utc};
+use chrono_humanize::HumanTime;
+use git2::{Branch, Commit, DiffOptions, Repository, Sort, Status, StatusOptions, Tree};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+/// A git work tree read in process with libgit2, so no `git` binary is
+/// needed. The status of every file is read once when the repository is
+/// discovered; branch and commit lookups reopen the repository on demand,
+/// which keeps this type `Send + Sync`.
+pub struct GitRepo {
+    root: PathBuf,
+    statuses: HashMap<String, String>,
+    dir_statuses: HashMap<String, String>,
+    tracked_dirs: HashSet<String>,
+    branch: OnceLock<Option<BranchInfo>>,
+    head_commit: OnceLock<Option<CommitInfo>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchInfo {
+    /// Branch name, or `(detached)` when HEAD doesn't point to a branch.
+    pub name: String,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitInfo {
+    pub short_id: String,
+    pub summary: String,
+    pub author: String,
+    /// Commit time in seconds since the Unix epoch.
+    pub time: i64,
+}
+
+impl CommitInfo {
+    fn from_commit(commit: &Commit) -> Self {
+        Self {
+            short_id: commit
+                .as_object()
+                .short_id()
+                .ok()
+                .and_then(|id| id.as_str().map(str::to_string))
+                .unwrap_or_else(|| commit.id().to_string()[..7].to_string()),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            author: commit.author().name().unwrap_or_default().to_string(),
+            time: commit.time().seconds(),
+        }
+    }
+
+    /// Commit time relative to now, e.g. `3 days ago`.
+    pub fn relative_time(&self) -> String {
+        Utc.timestamp_opt(self.time, 0)
+            .single()
+            .map(|time| HumanTime::from(time).to_string())
+            .unwrap_or_default()
+    }
+}
+
+impl GitRepo {
+    /// Opens the repository containing `path` and reads the status of all its
+    /// files, including untracked ones.
+    pub fn discover(path: &Path) -> Option<Self> {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            path.parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new("."))
+        };
+        let repo = Repository::discover(dir).ok()?;
+        let root = fs::canonicalize(repo.workdir()?).ok()?;
+
+        let mut statuses = HashMap::new();
+        if let Ok(index) = repo.index() {
+            for entry in index.iter() {
+                statuses.insert(
+                    String::from_utf8_lossy(&entry.path).into_owned(),
+                    ".".to_string(),
+                );
+            }
+        }
+
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .renames_head_to_index(true);
+        if let Ok(entries) = repo.statuses(Some(&mut options)) {
+            for entry in entries.iter() {
+                if let Some(path) = entry.path() {
+                    statuses.insert(path.to_string(), porcelain_status(entry.status()));
+                }
+            }
+        }
+
+        let dir_statuses = rollup_statuses(&statuses);
+        let tracked_dirs = tracked_dirs(&statuses);
+        Some(Self {
+            root,
+            statuses,
+            dir_statuses,
+            tracked_dirs,
+            branch: OnceLock::new(),
+            head_commit: OnceLock::new(),
+        })
+    }
+
+    /// Canonical path of the work tree.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Two-character porcelain status of a file such as `M.`, `.M`, `??` or
+    /// `UU`, or `.` for a clean tracked file. `None` outside the repository
+    /// and for directories.
+    pub fn status(&self, path: &Path) -> Option<&str> {
+        let relative = self.relative(path)?;
+        self.statuses.get(&relative).map(String::as_str)
+    }
+
+    /// Combined status of the changed files below a directory, e.g. `.M` for
+    /// a directory containing modified files. `None` when nothing below it
+    /// changed.
+    pub fn dir_status(&self, path: &Path) -> Option<&str> {
+        let relative = self.relative(path)?;
+        self.dir_statuses.get(&relative).map(String::as_str)
+    }
+
+    /// Statuses of the changed files at or below `path`, keyed by their path
+    /// relative to the work tree.
+    pub fn changes_in(&self, path: &Path) -> Vec<(&str, &str)> {
+        let Some(relative) = self.relative(path) else {
+            return Vec::new();
+        };
+        self.statuses
+            .iter()
+            .filter(|(file, status)| *status != "." && is_within(file, &relative))
+            .map(|(file, status)| (file.as_str(), status.as_str()))
+            .collect()
+    }
+
+    pub fn branch(&self) -> Option<BranchInfo> {
+        self.branch.get_or_init(|| self.read_branch()).clone()
+    }
+
+    fn read_branch(&self) -> Option<BranchInfo> {
+        let repo = Repository::open(&self.root).ok()?;
+        let head = repo.find_reference("HEAD").ok()?;
+        let name = match head.symbolic_target() {
+            Some(target) => target.strip_prefix("refs/heads/").unwrap_or(target),
+            None => "(detached)",
+        }
+        .to_string();
+        let (ahead, behind) = ahead_behind(&repo).unwrap_or_default();
+        Some(BranchInfo {
+            name,
+            ahead,
+            behind,
+        })
+    }
+
+    pub fn head_commit(&self) -> Option<CommitInfo> {
+        self.head_commit
+            .get_or_init(|| self.read_head_commit())
+            .clone()
+    }
+
+    fn read_head_commit(&self) -> Option<CommitInfo> {
+        let repo = Repository::open(&self.root).ok()?;
+        let commit = repo.head().ok()?.peel_to_commit().ok()?;
+        Some(CommitInfo::from_commit(&commit))
+    }
+
+    /// The last commit touching each of `paths`, like `git log -1 -- <path>`
+    /// per path but found in a single walk of the history. Directories match
+    /// commits touching anything below them. Paths without a commit are left
+    /// out of the result; untracked paths are skipped without a walk.
+    pub fn last_commits<P: AsRef<Path>>(&self, paths: &[P]) -> HashMap<PathBuf, CommitInfo> {
+        let mut found = HashMap::new();
+        let mut pending: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for path in paths {
+            let path = path.as_ref();
+            if let Some(relative) = self.relative(path).filter(|r| self.is_tracked(r)) {
+                pending
+                    .entry(relative)
+                    .or_default()
+                    .push(path.to_path_buf());
+            }
+        }
+
+        if pending.is_empty() {
+            return found;
+        }
+        let Ok(repo) = Repository::open(&self.root) else {
+            return found;
+        };
+        let Ok(mut walk) = repo.revwalk() else {
+            return found;
+        };
+        if walk.push_head().is_err() || walk.set_sorting(Sort::TIME).is_err() {
+            return found;
+        }
+
+        for oid in walk.flatten() {
+            if pending.is_empty() {
+                break;
+            }
+            let Ok(commit) = repo.find_commit(oid) else {
+                continue;
+            };
+            let changed = changed_paths(&repo, &commit, pending.keys());
+            if changed.is_empty() {
+                continue;
+            }
+
+            let info = CommitInfo::from_commit(&commit);
+            pending.retain(|relative, inputs| {
+                let touched = changed.iter().any(|file| is_within(file, relative));
+                if touched {
+                    for input in inputs.drain(..) {
+                        found.insert(input, info.clone());
+                    }
+                }
+                !touched
+            });
+        }
+
+        found
+    }
+
+    pub fn last_commit(&self, path: &Path) -> Option<CommitInfo> {
+        self.last_commits(&[path]).into_values().next()
+    }
+
+    /// Whether a path relative to the work tree is a tracked file or a
+    /// directory holding one, i.e. whether any commit can have touched it.
+    fn is_tracked(&self, relative: &str) -> bool {
+        match self.statuses.get(relative) {
+            Some(status) => status != "??" && status != "!!",
+            None => self.tracked_dirs.contains(relative),
+        }
+    }
+
+    fn relative(&self, path: &Path) -> Option<String> {
+        let full = if path.as_os_str().is_empty() || path == Path::new(".") {
+            fs::canonicalize(".").ok()?
+        } else {
+            let dir = canonical_parent(path)?;
+            match path.file_name() {
+                Some(name) => dir.join(name),
+                None => fs::canonicalize(path).ok()?,
+            }
+        };
+        let relative = full.strip_prefix(&self.root).ok()?;
+        Some(relative.to_string_lossy().into_owned())
+    }
+}
+
+/// Repositories and last commits for plugins, which are asked about one
+/// entry at a time: each repository is read once, and the last commits of
+/// all entries of a directory are looked up together on the first request.
+#[derive(Default)]
+pub struct GitRepoCache {
+    roots: HashMap<PathBuf, Option<PathBuf>>,
+    repos: HashMap<PathBuf, Option<Arc<GitRepo>>>,
+    commits: HashMap<PathBuf, Option<CommitInfo>>,
+}
+
+impl GitRepoCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The repository containing `path`, found from its parent directory.
+    pub fn repo(&mut self, path: &Path) -> Option<Arc<GitRepo>> {
+        let dir = canonical_parent(path)?;
+        let root = self
+            .roots
+            .entry(dir.clone())
+            .or_insert_with(|| {
+                dir.ancestors()
+                    .find(|ancestor| ancestor.join(".git").exists())
+                    .map(Path::to_path_buf)
+            })
+            .clone()?;
+        self.repos
+            .entry(root.clone())
+            .or_insert_with(|| GitRepo::discover(&root).map(Arc::new))
+            .clone()
+    }
+
+    pub fn last_commit(&mut self, path: &Path) -> Option<CommitInfo> {
+        let dir = canonical_parent(path)?;
+        let full = dir.join(path.file_name()?);
+        if let Some(commit) = self.commits.get(&full) {
+            return commit.clone();
+        }
+
+        let repo = self.repo(path)?;
+        let siblings: Vec<PathBuf> = match fs::read_dir(&dir) {
+            Ok(entries) => entries.flatten().map(|entry| entry.path()).collect(),
+            Err(_) => vec![full.clone()],
+        };
+        let mut found = repo.last_commits(&siblings);
+        for sibling in siblings {
+            let commit = found.remove(&sibling);
+            self.commits.insert(sibling, commit);
+        }
+        self.commits.entry(full).or_default().clone()
+    }
+}
+
+fn canonical_parent(path: &Path) -> Option<PathBuf> {
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty());
+    fs::canonicalize(parent.unwrap_or(Path::new("."))).ok()
+}
+
+/// Whether `file` is `dir` itself or below it; the empty path is the root.
+fn is_within(file: &str, dir: &str) -> bool {
+    dir.is_empty()
+        || file == dir
+        || (file.starts_with(dir) && file.as_bytes().get(dir.len()) == Some(&b'/'))
+}
+
+fn porcelain_status(status: Status) -> String {
+    if status.is_conflicted() {
+        return "UU".to_string();
+    }
+    if status.is_ignored() {
+        return "!!".to_string();
+    }
+    if status == Status::WT_NEW {
+        return "??".to_string();
+    }
+
+    let index = if status.is_index_new() {
+        'A'
+    } else if status.is_index_modified() {
+        'M'
+    } else if status.is_index_deleted() {
+        'D'
+    } else if status.is_index_renamed() {
+        'R'
+    } else if status.is_index_typechange() {
+        'T'
+    } else {
+        '.'
+    };
+    let worktree = if status.is_wt_modified() {
+        'M'
+    } else if status.is_wt_deleted() {
+        'D'
+    } else if status.is_wt_renamed() {
+        'R'
+    } else if status.is_wt_typechange() {
+        'T'
+    } else {
+        '.'
+    };
+    format!("{}{}", index, worktree)
+}
+
+/// Order in which a directory's rollup picks the state of its files, most
+/// important first.
+const ROLLUP_PRIORITY: &[char] = &['U', 'M', 'A', 'D', 'R', 'T', '?'];
+
+/// Rolls the status of changed files up into every directory above them.
+/// Each side of the status shows the most important state found below, and
+/// a directory holding only untracked files is `??`.
+fn rollup_statuses(statuses: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut states: HashMap<String, (Vec<char>, Vec<char>)> = HashMap::new();
+    for (file, status) in statuses {
+        if status == "." || status == "!!" {
+            continue;
+        }
+        let mut chars = status.chars();
+        let index = chars.next().unwrap_or('.');
+        let worktree = chars.next().unwrap_or('.');
+
+        let mut dir = Path::new(file).parent();
+        while let Some(current) = dir {
+            let (indexes, worktrees) = states
+                .entry(current.to_string_lossy().into_owned())
+                .or_default();
+            indexes.push(index);
+            worktrees.push(worktree);
+            dir = current.parent();
+        }
+    }
+
+    let pick = |chars: &[char]| {
+        ROLLUP_PRIORITY
+            .iter()
+            .copied()
+            .find(|c| chars.contains(c))
+            .unwrap_or('.')
+    };
+    states
+        .into_iter()
+        .map(|(dir, (indexes, worktrees))| {
+            let status = match (pick(&indexes), pick(&worktrees)) {
+                ('?', '?') => "??".to_string(),
+                ('?', worktree) => format!(".{}", worktree),
+                (index, '?') => format!("{}.", index),
+                (index, worktree) => format!("{}{}", index, worktree),
+            };
+            (dir, status)
+        })
+        .collect()
+}
+
+/// Every directory above a tracked file, with `""` for the work tree root.
+fn tracked_dirs(statuses: &HashMap<String, String>) -> HashSet<String> {
+    let mut dirs = HashSet::new();
+    for (file, status) in statuses {
+        if status == "??" || status == "!!" {
+            continue;
+        }
+        let mut dir = Path::new(file).parent();
+        while let Some(current) = dir {
+            if !dirs.insert(current.to_string_lossy().into_owned()) {
+                break;
+            }
+            dir = current.parent();
+        }
+    }
+    dirs
+}
+
+fn ahead_behind(repo: &Repository) -> Option<(usize, usize)> {
+    let head = repo.head().ok()?;
+    let local = head.target()?;
+    let upstream = Branch::wrap(head).upstream().ok()?;
+    let remote = upstream.get().target()?;
+    repo.graph_ahead_behind(local, remote).ok()
+}
+
+/// Paths a commit changed among `pathspecs`. Like git's history
+/// simplification, a merge only counts for paths that differ from every
+/// parent.
+fn changed_paths<'a>(
+    repo: &Repository,
+    commit: &Commit,
+    pathspecs: impl Iterator<Item = &'a String>,
+) -> HashSet<String> {
+    let Ok(tree) = commit.tree() else {
+        return HashSet::new();
+    };
+
+    let mut options = DiffOptions::new();
+    options.disable_pathspec_match(true);
+    let mut whole_tree = false;
+    for pathspec in pathspecs {
+        if pathspec.is_empty() {
+            whole_tree = true;
+        } else {
+            options.pathspec(pathspec);
+        }
+    }
+    if whole_tree {
+        options = DiffOptions::new();
+    }
+
+    let parents: Vec<Tree> = commit
+        .parents()
+        .filter_map(|parent| parent.tree().ok())
+        .collect();
+    if parents.is_empty() {
+        return diff_paths(repo, None, &tree, &mut options);
+    }
+
+    let mut changed: Option<HashSet<String>> = None;
+    for parent in &parents {
+        let paths = diff_paths(repo, Some(parent), &tree, &mut options);
+        changed = Some(match changed {
+            Some(previous) => previous.intersection(&paths).cloned().collect(),
+            None => paths,
+        });
+        if changed.as_ref().is_some_and(HashSet::is_empty) {
+            break;
+        }
+    }
+    changed.unwrap_or_default()
+}
+
+fn diff_paths(
+    repo: &Repository,
+    old: Option<&Tree>,
+    new: &Tree,
+    options: &mut DiffOptions,
+) -> HashSet<String> {
+    let Ok(diff) = repo.diff_tree_to_tree(old, Some(new), Some(options)) else {
+        return HashSet::new();
+    };
+    diff.deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect()
+}
@@ -1,6 +1,8 @@
 pub mod actions;
 pub mod config;
 pub mod format;
+#[cfg(feature = "git")]
+pub mod git;
 pub mod syntax;
 pub mod ui;
 
@@ -67,54 +69,56 @@ pub trait ProtobufHandler {
             Some(proto::plugin_message::Message::GetSupportedFormats(_)) => {
                 Ok(PluginRequest::GetSupportedFormats)
             }
+            Some(proto::plugin_message::Message::Handshake(handshake)) => {
+                Ok(PluginRequest::Handshake(handshake.into()))
+            }
             Some(proto::plugin_message::Message::Decorate(entry)) => {
-                let metadata = entry
-                    .metadata
-                    .map(|m| lla_plugin_interface::EntryMetadata {
-                        size: m.size,
-                        modified: m.modified,
-                        accessed: m.accessed,
-                        created: m.created,
-                        is_dir: m.is_dir,
-                        is_file: m.is_file,
-                        is_symlink: m.is_symlink,
-                        permissions: m.permissions,
-                        uid: m.uid,
-                        gid: m.gid,
-                    })
-                    .ok_or("Missing metadata in decorated entry")?;
-
-                let decorated = lla_plugin_interface::DecoratedEntry {
-                    path: std::path::PathBuf::from(entry.path),
-                    metadata,
-                    custom_fields: entry.custom_fields,
-                };
-                Ok(PluginRequest::Decorate(decorated))
+                Ok(PluginRequest::Decorate(decode_entry(entry)?))
+            }
+            Some(proto::plugin_message::Message::DecorateBatch(batch)) => {
+                let entries = batch
+                    .entries
+                    .into_iter()
+                    .map(decode_entry)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(PluginRequest::DecorateBatch(entries))
             }
             Some(proto::plugin_message::Message::FormatField(req)) => {
                 let entry = req.entry.ok_or("Missing entry in format field request")?;
-                let metadata = entry
-                    .metadata
-                    .map(|m| lla_plugin_interface::EntryMetadata {
-                        size: m.size,
-                        modified: m.modified,
-                        accessed: m.accessed,
-                        created: m.created,
-                        is_dir: m.is_dir,
-                        is_file: m.is_file,
-                        is_symlink: m.is_symlink,
-                        permissions: m.permissions,
-                        uid: m.uid,
-                        gid: m.gid,
-                    })
-                    .ok_or("Missing metadata in decorated entry")?;
-
-                let decorated = lla_plugin_interface::DecoratedEntry {
-                    path: std::path::PathBuf::from(entry.path),
-                    metadata,
-                    custom_fields: entry.custom_fields,
-                };
-                Ok(PluginRequest::FormatField(decorated, req.format))
+                Ok(PluginRequest::FormatField(decode_entry(entry)?, req.format))
+            }
+            Some(proto::plugin_message::Message::GetFormatters(_)) => {
+                Ok(PluginRequest::GetFormatters)
+            }
+            Some(proto::plugin_message::Message::FormatEntries(req)) => {
+                let entries = req
+                    .entries
+                    .into_iter()
+                    .map(decode_entry)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(PluginRequest::FormatEntries(req.formatter, entries))
+            }
+            Some(proto::plugin_message::Message::GetSorters(_)) => Ok(PluginRequest::GetSorters),
+            Some(proto::plugin_message::Message::GetFilters(_)) => Ok(PluginRequest::GetFilters),
+            Some(proto::plugin_message::Message::SortKeys(req)) => {
+                let entries = req
+                    .entries
+                    .into_iter()
+                    .map(decode_entry)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(PluginRequest::SortKeys(req.sorter, entries))
+            }
+            Some(proto::plugin_message::Message::FilterEntries(req)) => {
+                let entries = req
+                    .entries
+                    .into_iter()
+                    .map(decode_entry)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(PluginRequest::FilterEntries(
+                    req.filter,
+                    req.argument,
+                    entries,
+                ))
             }
             Some(proto::plugin_message::Message::Action(req)) => {
                 Ok(PluginRequest::PerformAction(req.action, req.args))
@@ -138,32 +142,44 @@ pub trait ProtobufHandler {
                     formats,
                 })
             }
+            PluginResponse::Handshake(handshake) => {
+                proto::plugin_message::Message::HandshakeResponse(handshake.into())
+            }
             PluginResponse::Decorated(entry) => {
-                let proto_metadata = proto::EntryMetadata {
-                    size: entry.metadata.size,
-                    modified: entry.metadata.modified,
-                    accessed: entry.metadata.accessed,
-                    created: entry.metadata.created,
-                    is_dir: entry.metadata.is_dir,
-                    is_file: entry.metadata.is_file,
-                    is_symlink: entry.metadata.is_symlink,
-                    permissions: entry.metadata.permissions,
-                    uid: entry.metadata.uid,
-                    gid: entry.metadata.gid,
-                };
-
-                let proto_entry = proto::DecoratedEntry {
-                    path: entry.path.to_string_lossy().to_string(),
-                    metadata: Some(proto_metadata),
-                    custom_fields: entry.custom_fields,
-                };
-                proto::plugin_message::Message::DecoratedResponse(proto_entry)
+                proto::plugin_message::Message::DecoratedResponse(entry.into())
+            }
+            PluginResponse::DecoratedBatch(entries) => {
+                proto::plugin_message::Message::DecoratedBatchResponse(proto::DecorateBatch {
+                    entries: entries.into_iter().map(Into::into).collect(),
+                })
             }
             PluginResponse::FormattedField(field) => {
                 proto::plugin_message::Message::FieldResponse(proto::FormattedFieldResponse {
                     field,
                 })
             }
+            PluginResponse::Formatters(formatters) => {
+                proto::plugin_message::Message::FormattersResponse(proto::FormattersResponse {
+                    formatters,
+                })
+            }
+            PluginResponse::FormattedEntries(output) => {
+                proto::plugin_message::Message::FormattedEntriesResponse(output)
+            }
+            PluginResponse::Sorters(sorters) => {
+                proto::plugin_message::Message::SortersResponse(proto::SortersResponse { sorters })
+            }
+            PluginResponse::Filters(filters) => {
+                proto::plugin_message::Message::FiltersResponse(proto::FiltersResponse { filters })
+            }
+            PluginResponse::SortKeys(keys) => {
+                proto::plugin_message::Message::SortKeysResponse(proto::SortKeysResponse { keys })
+            }
+            PluginResponse::FilterResults(matches) => {
+                proto::plugin_message::Message::FilterEntriesResponse(
+                    proto::FilterEntriesResponse { matches },
+                )
+            }
             PluginResponse::ActionResult(result) => match result {
                 Ok(()) => proto::plugin_message::Message::ActionResponse(proto::ActionResponse {
                     success: true,
@@ -175,6 +191,9 @@ pub trait ProtobufHandler {
                 }),
             },
             PluginResponse::Error(e) => proto::plugin_message::Message::ErrorResponse(e),
+            _ => proto::plugin_message::Message::ErrorResponse(
+                "Response not supported by this version of lla_plugin_utils".to_string(),
+            ),
         };
 
         let proto_msg = proto::PluginMessage {
@@ -185,6 +204,13 @@ pub trait ProtobufHandler {
         buf.to_vec()
     }
 
+    /// The answer to a request the plugin doesn't handle, for the catch-all
+    /// arm of its request match. The host only sends optional requests to
+    /// plugins announcing their capability, so this is rarely seen.
+    fn unsupported_request(&self, request: &PluginRequest) -> PluginResponse {
+        PluginResponse::Error(format!("Unsupported request: {}", request.name()))
+    }
+
     fn encode_error(&self, error: &str) -> Vec<u8> {
         use prost::Message;
         let error_msg = proto::PluginMessage {
@@ -198,6 +224,19 @@ pub trait ProtobufHandler {
     }
 }
 
+fn decode_entry(
+    entry: proto::DecoratedEntry,
+) -> Result<lla_plugin_interface::DecoratedEntry, String> {
+    let metadata = entry
+        .metadata
+        .ok_or("Missing metadata in decorated entry")?;
+    Ok(lla_plugin_interface::DecoratedEntry {
+        path: std::path::PathBuf::from(entry.path),
+        metadata: metadata.into(),
+        custom_fields: entry.custom_fields,
+    })
+}
+
 #[macro_export]
 macro_rules! plugin_action {
     ($registry:expr, $name:expr, $usage:expr, $description:expr, $examples:expr, $handler:expr) => {
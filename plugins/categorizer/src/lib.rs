@@ -1,5 +1,7 @@
 use lazy_static::lazy_static;
-use lla_plugin_interface::{DecoratedEntry, Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::{
@@ -386,6 +388,36 @@ impl FileCategoryPlugin {
             _ => None,
         }
     }
+
+    fn in_category(&self, entry: &DecoratedEntry, name: &str) -> bool {
+        PluginState::get_category_info(&self.config().rules, entry).is_some_and(
+            |(category, _, subcategory)| {
+                category.eq_ignore_ascii_case(name)
+                    || subcategory.is_some_and(|sub| sub.eq_ignore_ascii_case(name))
+            },
+        )
+    }
+
+    fn decorate(&mut self, mut entry: DecoratedEntry) -> DecoratedEntry {
+        let mut state = PLUGIN_STATE.write();
+        if let Some((category, color, subcategory)) =
+            PluginState::get_category_info(&self.config().rules, &entry)
+        {
+            entry
+                .custom_fields
+                .insert("category".to_string(), category.clone());
+            entry
+                .custom_fields
+                .insert("category_color".to_string(), color);
+            if let Some(sub) = &subcategory {
+                entry
+                    .custom_fields
+                    .insert("subcategory".to_string(), sub.clone());
+            }
+            state.update_stats(&entry, &category, subcategory.as_deref());
+        }
+        entry
+    }
 }
 
 impl Plugin for FileCategoryPlugin {
@@ -393,6 +425,12 @@ impl Plugin for FileCategoryPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[
+                            capability::DECORATE_BATCH,
+                            capability::FILTERS,
+                        ]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -406,34 +444,38 @@ impl Plugin for FileCategoryPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        let mut state = PLUGIN_STATE.write();
-                        if let Some((category, color, subcategory)) =
-                            PluginState::get_category_info(&self.config().rules, &entry)
-                        {
-                            entry
-                                .custom_fields
-                                .insert("category".to_string(), category.clone());
-                            entry
-                                .custom_fields
-                                .insert("category_color".to_string(), color);
-                            if let Some(sub) = &subcategory {
-                                entry
-                                    .custom_fields
-                                    .insert("subcategory".to_string(), sub.clone());
-                            }
-                            state.update_stats(&entry, &category, subcategory.as_deref());
-                        }
-                        PluginResponse::Decorated(entry)
+                    PluginRequest::Decorate(entry) => {
+                        PluginResponse::Decorated(self.decorate(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries
+                            .into_iter()
+                            .map(|entry| self.decorate(entry))
+                            .collect(),
+                    ),
                     PluginRequest::FormatField(entry, format) => {
                         let field = self.format_file_info(&entry, &format);
                         PluginResponse::FormattedField(field)
                     }
+                    PluginRequest::GetFilters => {
+                        PluginResponse::Filters(vec!["category".to_string()])
+                    }
+                    PluginRequest::FilterEntries(filter, argument, entries) => {
+                        match filter.as_str() {
+                            "category" => PluginResponse::FilterResults(
+                                entries
+                                    .iter()
+                                    .map(|entry| self.in_category(entry, &argument))
+                                    .collect(),
+                            ),
+                            _ => PluginResponse::Error(format!("Unknown filter: {}", filter)),
+                        }
+                    }
                     PluginRequest::PerformAction(action, args) => {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
@@ -1,5 +1,7 @@
 use lazy_static::lazy_static;
-use lla_plugin_interface::{DecoratedEntry, Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::{
@@ -594,6 +596,51 @@ impl CodeComplexityEstimatorPlugin {
                     .format_metrics(&metrics, format == "long")
             })
     }
+
+    fn complexity_key(&self, entry: &DecoratedEntry) -> String {
+        if !entry.path.is_file() {
+            return String::new();
+        }
+        PLUGIN_STATE
+            .read()
+            .analyze_file(&entry.path)
+            .map(|metrics| metrics.cyclomatic_complexity.to_string())
+            .unwrap_or_default()
+    }
+
+    fn decorate(&mut self, mut entry: DecoratedEntry) -> DecoratedEntry {
+        if entry.path.is_file() {
+            let metrics = PLUGIN_STATE.read().analyze_file(&entry.path);
+            if let Some(metrics) = metrics {
+                entry.custom_fields.insert(
+                    "complexity_metrics".to_string(),
+                    toml::to_string(&metrics).unwrap_or_default(),
+                );
+
+                if let Some(ext) = entry.path.extension().and_then(|e| e.to_str()) {
+                    let lang = {
+                        let state = PLUGIN_STATE.read();
+                        state
+                            .config
+                            .languages
+                            .iter()
+                            .find(|(_, rules)| rules.extensions.iter().any(|e| e == ext))
+                            .map(|(lang, _)| lang.clone())
+                    };
+
+                    if let Some(lang) = lang {
+                        PLUGIN_STATE
+                            .write()
+                            .stats
+                            .entry(lang)
+                            .or_default()
+                            .push((entry.path.clone(), metrics));
+                    }
+                }
+            }
+        }
+        entry
+    }
 }
 
 impl Plugin for CodeComplexityEstimatorPlugin {
@@ -601,6 +648,12 @@ impl Plugin for CodeComplexityEstimatorPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[
+                            capability::DECORATE_BATCH,
+                            capability::SORTERS,
+                        ]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -614,49 +667,36 @@ impl Plugin for CodeComplexityEstimatorPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        if entry.path.is_file() {
-                            let metrics = PLUGIN_STATE.read().analyze_file(&entry.path);
-                            if let Some(metrics) = metrics {
-                                entry.custom_fields.insert(
-                                    "complexity_metrics".to_string(),
-                                    toml::to_string(&metrics).unwrap_or_default(),
-                                );
-
-                                if let Some(ext) = entry.path.extension().and_then(|e| e.to_str()) {
-                                    let lang = {
-                                        let state = PLUGIN_STATE.read();
-                                        state
-                                            .config
-                                            .languages
-                                            .iter()
-                                            .find(|(_, rules)| {
-                                                rules.extensions.iter().any(|e| e == ext)
-                                            })
-                                            .map(|(lang, _)| lang.clone())
-                                    };
-
-                                    if let Some(lang) = lang {
-                                        PLUGIN_STATE
-                                            .write()
-                                            .stats
-                                            .entry(lang)
-                                            .or_default()
-                                            .push((entry.path.clone(), metrics));
-                                    }
-                                }
-                            }
-                        }
-                        PluginResponse::Decorated(entry)
+                    PluginRequest::Decorate(entry) => {
+                        PluginResponse::Decorated(self.decorate(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries
+                            .into_iter()
+                            .map(|entry| self.decorate(entry))
+                            .collect(),
+                    ),
                     PluginRequest::FormatField(entry, format) => {
                         let field = self.format_file_info(&entry, &format);
                         PluginResponse::FormattedField(field)
                     }
+                    PluginRequest::GetSorters => {
+                        PluginResponse::Sorters(vec!["complexity".to_string()])
+                    }
+                    PluginRequest::SortKeys(sorter, entries) => match sorter.as_str() {
+                        "complexity" => PluginResponse::SortKeys(
+                            entries
+                                .iter()
+                                .map(|entry| self.complexity_key(entry))
+                                .collect(),
+                        ),
+                        _ => PluginResponse::Error(format!("Unknown sorter: {}", sorter)),
+                    },
                     PluginRequest::PerformAction(action, args) => {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
@@ -6,7 +6,9 @@ use dialoguer::{MultiSelect, Select};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use lazy_static::lazy_static;
-use lla_plugin_interface::{Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::components::{BoxComponent, BoxStyle, HelpFormatter, LlaDialoguerTheme},
@@ -1221,6 +1223,19 @@ impl CodeSnippetExtractorPlugin {
 
         Ok(())
     }
+
+    fn decorate(&mut self, mut entry: DecoratedEntry) -> DecoratedEntry {
+        if let Some(file_path) = entry.path.to_str() {
+            let snippet_count = self.list_snippets_by_file(file_path).len();
+            if snippet_count > 0 {
+                entry.custom_fields.insert(
+                    "snippet_count".to_string(),
+                    format!("[{} snippets]", snippet_count),
+                );
+            }
+        }
+        entry
+    }
 }
 
 impl Deref for CodeSnippetExtractorPlugin {
@@ -1236,6 +1251,9 @@ impl Plugin for CodeSnippetExtractorPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -1249,18 +1267,15 @@ impl Plugin for CodeSnippetExtractorPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        if let Some(file_path) = entry.path.to_str() {
-                            let snippet_count = self.list_snippets_by_file(file_path).len();
-                            if snippet_count > 0 {
-                                entry.custom_fields.insert(
-                                    "snippet_count".to_string(),
-                                    format!("[{} snippets]", snippet_count),
-                                );
-                            }
-                        }
-                        PluginResponse::Decorated(entry)
+                    PluginRequest::Decorate(entry) => {
+                        PluginResponse::Decorated(self.decorate(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries
+                            .into_iter()
+                            .map(|entry| self.decorate(entry))
+                            .collect(),
+                    ),
                     PluginRequest::FormatField(entry, format) => {
                         let field = if format == "snippet_count" {
                             entry.custom_fields.get("snippet_count").cloned()
@@ -1273,6 +1288,7 @@ impl Plugin for CodeSnippetExtractorPlugin {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
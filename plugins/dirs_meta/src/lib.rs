@@ -1,5 +1,7 @@
 use lazy_static::lazy_static;
-use lla_plugin_interface::{DecoratedEntry, Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::{
@@ -378,6 +380,25 @@ impl DirsPlugin {
             _ => None,
         }
     }
+
+    fn decorate(mut entry: DecoratedEntry) -> DecoratedEntry {
+        if entry.metadata.is_dir {
+            let result = Self::analyze_directory(&entry.path);
+
+            if let Some((file_count, dir_count, total_size)) = result {
+                entry
+                    .custom_fields
+                    .insert("dir_file_count".to_string(), file_count.to_string());
+                entry
+                    .custom_fields
+                    .insert("dir_subdir_count".to_string(), dir_count.to_string());
+                entry
+                    .custom_fields
+                    .insert("dir_total_size".to_string(), format_size(total_size));
+            }
+        }
+        entry
+    }
 }
 
 impl Plugin for DirsPlugin {
@@ -385,6 +406,9 @@ impl Plugin for DirsPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -398,24 +422,12 @@ impl Plugin for DirsPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        if entry.metadata.is_dir {
-                            let result = Self::analyze_directory(&entry.path);
-
-                            if let Some((file_count, dir_count, total_size)) = result {
-                                entry
-                                    .custom_fields
-                                    .insert("dir_file_count".to_string(), file_count.to_string());
-                                entry
-                                    .custom_fields
-                                    .insert("dir_subdir_count".to_string(), dir_count.to_string());
-                                entry
-                                    .custom_fields
-                                    .insert("dir_total_size".to_string(), format_size(total_size));
-                            }
-                        }
-                        PluginResponse::Decorated(entry)
+                    PluginRequest::Decorate(entry) => {
+                        PluginResponse::Decorated(Self::decorate(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries.into_par_iter().map(Self::decorate).collect(),
+                    ),
                     PluginRequest::FormatField(entry, format) => {
                         let field = self.format_directory_info(&entry, &format);
                         PluginResponse::FormattedField(field)
@@ -424,6 +436,7 @@ impl Plugin for DirsPlugin {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
@@ -1,5 +1,7 @@
 use lazy_static::lazy_static;
-use lla_plugin_interface::{DecoratedEntry, Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::{
@@ -342,6 +344,9 @@ impl Plugin for DuplicateFileDetectorPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -358,6 +363,12 @@ impl Plugin for DuplicateFileDetectorPlugin {
                     PluginRequest::Decorate(entry) => {
                         PluginResponse::Decorated(self.process_entry(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries
+                            .into_iter()
+                            .map(|entry| self.process_entry(entry))
+                            .collect(),
+                    ),
                     PluginRequest::FormatField(entry, format) => {
                         let field = self.format_duplicate_info(&entry, &format);
                         PluginResponse::FormattedField(field)
@@ -366,6 +377,7 @@ impl Plugin for DuplicateFileDetectorPlugin {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
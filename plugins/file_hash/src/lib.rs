@@ -1,11 +1,14 @@
 use lazy_static::lazy_static;
-use lla_plugin_interface::{Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::components::{BoxComponent, BoxStyle, HelpFormatter, KeyValue, List, Spinner},
     ActionRegistry, BasePlugin, ConfigurablePlugin, ProtobufHandler,
 };
 use parking_lot::RwLock;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use sha1::Sha1;
 use sha2::{Digest, Sha256};
@@ -184,6 +187,16 @@ impl FileHashPlugin {
 
         Some(format!("\n{}", list.render()))
     }
+
+    fn decorate(mut entry: DecoratedEntry) -> DecoratedEntry {
+        if entry.metadata.is_file {
+            if let Some((sha1, sha256)) = Self::calculate_hashes(&entry.path) {
+                entry.custom_fields.insert("sha1".to_string(), sha1);
+                entry.custom_fields.insert("sha256".to_string(), sha256);
+            }
+        }
+        entry
+    }
 }
 
 impl Plugin for FileHashPlugin {
@@ -191,6 +204,9 @@ impl Plugin for FileHashPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -204,20 +220,20 @@ impl Plugin for FileHashPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        if entry.metadata.is_file {
-                            let spinner = SPINNER.write();
-                            spinner.set_status("Calculating hashes...".to_string());
-
-                            if let Some((sha1, sha256)) = Self::calculate_hashes(&entry.path) {
-                                entry.custom_fields.insert("sha1".to_string(), sha1);
-                                entry.custom_fields.insert("sha256".to_string(), sha256);
-                            }
-
-                            spinner.finish();
-                        }
+                    PluginRequest::Decorate(entry) => {
+                        let spinner = SPINNER.write();
+                        spinner.set_status("Calculating hashes...".to_string());
+                        let entry = Self::decorate(entry);
+                        spinner.finish();
                         PluginResponse::Decorated(entry)
                     }
+                    PluginRequest::DecorateBatch(entries) => {
+                        let spinner = SPINNER.write();
+                        spinner.set_status("Calculating hashes...".to_string());
+                        let entries = entries.into_par_iter().map(Self::decorate).collect();
+                        spinner.finish();
+                        PluginResponse::DecoratedBatch(entries)
+                    }
                     PluginRequest::FormatField(entry, format) => {
                         let field = self.format_hash_info(&entry, &format);
                         PluginResponse::FormattedField(field)
@@ -226,6 +242,7 @@ impl Plugin for FileHashPlugin {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
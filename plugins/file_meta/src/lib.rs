@@ -1,5 +1,7 @@
 use lazy_static::lazy_static;
-use lla_plugin_interface::{DecoratedEntry, Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::{
@@ -201,6 +203,41 @@ impl FileMetadataPlugin {
             _ => None,
         }
     }
+
+    fn decorate(&mut self, mut entry: DecoratedEntry) -> DecoratedEntry {
+        entry.custom_fields.insert(
+            "accessed".to_string(),
+            Self::format_timestamp(
+                SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(entry.metadata.accessed),
+            ),
+        );
+        entry.custom_fields.insert(
+            "modified".to_string(),
+            Self::format_timestamp(
+                SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(entry.metadata.modified),
+            ),
+        );
+        entry.custom_fields.insert(
+            "created".to_string(),
+            Self::format_timestamp(
+                SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(entry.metadata.created),
+            ),
+        );
+        entry
+            .custom_fields
+            .insert("uid".to_string(), entry.metadata.uid.to_string());
+        entry
+            .custom_fields
+            .insert("gid".to_string(), entry.metadata.gid.to_string());
+        entry
+            .custom_fields
+            .insert("size".to_string(), entry.metadata.size.to_string());
+        entry.custom_fields.insert(
+            "permissions".to_string(),
+            entry.metadata.permissions.to_string(),
+        );
+        entry
+    }
 }
 
 impl Plugin for FileMetadataPlugin {
@@ -208,6 +245,9 @@ impl Plugin for FileMetadataPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -221,44 +261,15 @@ impl Plugin for FileMetadataPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        entry.custom_fields.insert(
-                            "accessed".to_string(),
-                            Self::format_timestamp(
-                                SystemTime::UNIX_EPOCH
-                                    + std::time::Duration::from_secs(entry.metadata.accessed),
-                            ),
-                        );
-                        entry.custom_fields.insert(
-                            "modified".to_string(),
-                            Self::format_timestamp(
-                                SystemTime::UNIX_EPOCH
-                                    + std::time::Duration::from_secs(entry.metadata.modified),
-                            ),
-                        );
-                        entry.custom_fields.insert(
-                            "created".to_string(),
-                            Self::format_timestamp(
-                                SystemTime::UNIX_EPOCH
-                                    + std::time::Duration::from_secs(entry.metadata.created),
-                            ),
-                        );
-                        entry
-                            .custom_fields
-                            .insert("uid".to_string(), entry.metadata.uid.to_string());
-                        entry
-                            .custom_fields
-                            .insert("gid".to_string(), entry.metadata.gid.to_string());
-                        entry
-                            .custom_fields
-                            .insert("size".to_string(), entry.metadata.size.to_string());
-                        entry.custom_fields.insert(
-                            "permissions".to_string(),
-                            entry.metadata.permissions.to_string(),
-                        );
-
-                        PluginResponse::Decorated(entry)
+                    PluginRequest::Decorate(entry) => {
+                        PluginResponse::Decorated(self.decorate(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries
+                            .into_iter()
+                            .map(|entry| self.decorate(entry))
+                            .collect(),
+                    ),
                     PluginRequest::FormatField(entry, format) => {
                         let field = self.format_file_info(&entry, &format);
                         PluginResponse::FormattedField(field)
@@ -267,6 +278,7 @@ impl Plugin for FileMetadataPlugin {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
@@ -1,7 +1,7 @@
 use colored::Colorize;
 use dialoguer::MultiSelect;
 use lazy_static::lazy_static;
-use lla_plugin_interface::{Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{capability, Handshake, Plugin, PluginRequest, PluginResponse};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::components::{BoxComponent, BoxStyle, HelpFormatter, LlaDialoguerTheme},
@@ -430,6 +430,9 @@ impl Plugin for FileMoverPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -443,11 +446,15 @@ impl Plugin for FileMoverPlugin {
                         PluginResponse::SupportedFormats(vec!["default".to_string()])
                     }
                     PluginRequest::Decorate(entry) => PluginResponse::Decorated(entry),
+                    PluginRequest::DecorateBatch(entries) => {
+                        PluginResponse::DecoratedBatch(entries)
+                    }
                     PluginRequest::FormatField(_, _) => PluginResponse::FormattedField(None),
                     PluginRequest::PerformAction(action, args) => {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
@@ -2,7 +2,7 @@ mod config;
 mod strategies;
 use colored::Colorize;
 use lazy_static::lazy_static;
-use lla_plugin_interface::{Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{capability, Handshake, Plugin, PluginRequest, PluginResponse};
 use lla_plugin_utils::{
     ui::components::{BoxComponent, BoxStyle, HelpFormatter},
     ActionRegistry, BasePlugin, ConfigurablePlugin, ProtobufHandler,
@@ -254,6 +254,9 @@ impl Plugin for FileOrganizerPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -267,11 +270,15 @@ impl Plugin for FileOrganizerPlugin {
                         PluginResponse::SupportedFormats(vec!["default".to_string()])
                     }
                     PluginRequest::Decorate(entry) => PluginResponse::Decorated(entry),
+                    PluginRequest::DecorateBatch(entries) => {
+                        PluginResponse::DecoratedBatch(entries)
+                    }
                     PluginRequest::FormatField(_, _) => PluginResponse::FormattedField(None),
                     PluginRequest::PerformAction(action, args) => {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
@@ -1,5 +1,7 @@
 use lazy_static::lazy_static;
-use lla_plugin_interface::{Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::{
@@ -325,6 +327,16 @@ impl FileTaggerPlugin {
             _ => None,
         }
     }
+
+    fn decorate(&mut self, mut entry: DecoratedEntry) -> DecoratedEntry {
+        let tags = self.get_tags(entry.path.to_str().unwrap_or(""));
+        if !tags.is_empty() {
+            entry
+                .custom_fields
+                .insert("tags".to_string(), tags.join(", "));
+        }
+        entry
+    }
 }
 
 impl Plugin for FileTaggerPlugin {
@@ -332,6 +344,9 @@ impl Plugin for FileTaggerPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -345,15 +360,15 @@ impl Plugin for FileTaggerPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        let tags = self.get_tags(entry.path.to_str().unwrap_or(""));
-                        if !tags.is_empty() {
-                            entry
-                                .custom_fields
-                                .insert("tags".to_string(), tags.join(", "));
-                        }
-                        PluginResponse::Decorated(entry)
+                    PluginRequest::Decorate(entry) => {
+                        PluginResponse::Decorated(self.decorate(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries
+                            .into_iter()
+                            .map(|entry| self.decorate(entry))
+                            .collect(),
+                    ),
                     PluginRequest::FormatField(entry, format) => {
                         let field = self.format_tags(&entry, &format);
                         PluginResponse::FormattedField(field)
@@ -362,6 +377,7 @@ impl Plugin for FileTaggerPlugin {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
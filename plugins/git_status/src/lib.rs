@@ -1,13 +1,16 @@
 use lazy_static::lazy_static;
-use lla_plugin_interface::{Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
+    git::GitRepoCache,
     ui::components::{BoxComponent, BoxStyle, HelpFormatter, KeyValue, List, Spinner},
     ActionRegistry, BasePlugin, ConfigurablePlugin, ProtobufHandler,
 };
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::Path, process::Command};
+use std::{collections::HashMap, path::Path};
 
 lazy_static! {
     static ref SPINNER: RwLock<Spinner> = RwLock::new(Spinner::new());
@@ -95,6 +98,7 @@ impl PluginConfig for GitConfig {}
 
 pub struct GitStatusPlugin {
     base: BasePlugin<GitConfig>,
+    repos: GitRepoCache,
 }
 
 impl GitStatusPlugin {
@@ -102,6 +106,7 @@ impl GitStatusPlugin {
         let plugin_name = env!("CARGO_PKG_NAME");
         let plugin = Self {
             base: BasePlugin::with_name(plugin_name),
+            repos: GitRepoCache::new(),
         };
         if let Err(e) = plugin.base.save_config() {
             eprintln!("[GitStatusPlugin] Failed to save config: {}", e);
@@ -109,58 +114,23 @@ impl GitStatusPlugin {
         plugin
     }
 
-    fn is_git_repo(path: &Path) -> bool {
-        let mut current_dir = Some(path);
-        while let Some(dir) = current_dir {
-            if dir.join(".git").exists() {
-                return true;
-            }
-            current_dir = dir.parent();
-        }
-        false
-    }
-
-    fn get_git_info(path: &Path) -> Option<(String, String, String)> {
-        if !Self::is_git_repo(path) {
-            return None;
-        }
-
-        let path_str = path.to_string_lossy();
-        let parent = path.parent().unwrap_or(path);
-
-        let status_output = Command::new("git")
-            .args(["status", "--porcelain", "--ignored"])
-            .arg(&*path_str)
-            .current_dir(parent)
-            .output()
-            .ok()?;
-
-        let branch_output = Command::new("git")
-            .args(["rev-parse", "--abbrev-ref", "HEAD"])
-            .current_dir(parent)
-            .output()
-            .ok()?;
-
-        let commit_output = Command::new("git")
-            .args(["log", "-1", "--format=%h %s"])
-            .current_dir(parent)
-            .output()
-            .ok()?;
-
-        let status = String::from_utf8(status_output.stdout).ok()?;
-        let branch = String::from_utf8(branch_output.stdout)
-            .ok()?
-            .trim()
-            .to_string();
-        let commit = String::from_utf8(commit_output.stdout)
-            .ok()?
-            .trim()
-            .to_string();
-
-        Some((status, branch, commit))
+    fn get_git_info(&mut self, path: &Path) -> Option<(Vec<String>, String, String)> {
+        let repo = self.repos.repo(path)?;
+        let statuses = repo
+            .changes_in(path)
+            .into_iter()
+            .map(|(_, status)| status.to_string())
+            .collect();
+        let branch = repo.branch().map(|branch| branch.name).unwrap_or_default();
+        let commit = repo
+            .head_commit()
+            .map(|commit| format!("{} {}", commit.short_id, commit.summary))
+            .unwrap_or_default();
+
+        Some((statuses, branch, commit))
     }
 
-    fn format_git_status(status: &str) -> (String, usize, usize, usize, usize) {
+    fn format_git_status(statuses: &[String]) -> (String, usize, usize, usize, usize) {
         let mut staged = 0;
         let mut modified = 0;
         let mut untracked = 0;
@@ -168,17 +138,17 @@ impl GitStatusPlugin {
 
         let mut formatted_entries = Vec::new();
 
-        for line in status.lines() {
-            let status_chars: Vec<char> = line.chars().take(2).collect();
-            let index_status = status_chars.first().copied().unwrap_or(' ');
-            let worktree_status = status_chars.get(1).copied().unwrap_or(' ');
+        for status in statuses {
+            let mut status_chars = status.chars();
+            let index_status = status_chars.next().unwrap_or('.');
+            let worktree_status = status_chars.next().unwrap_or('.');
 
             match (index_status, worktree_status) {
-                ('M', ' ') => {
+                ('M', '.') => {
                     staged += 1;
                     formatted_entries.push("staged");
                 }
-                (' ', 'M') => {
+                ('.', 'M') => {
                     modified += 1;
                     formatted_entries.push("modified");
                 }
@@ -187,11 +157,11 @@ impl GitStatusPlugin {
                     modified += 1;
                     formatted_entries.push("staged & modified");
                 }
-                ('A', ' ') => {
+                ('A', '.') => {
                     staged += 1;
                     formatted_entries.push("new file");
                 }
-                ('D', ' ') | (' ', 'D') => {
+                ('D', '.') | ('.', 'D') => {
                     modified += 1;
                     formatted_entries.push("deleted");
                 }
@@ -207,7 +177,7 @@ impl GitStatusPlugin {
                     conflicts += 1;
                     formatted_entries.push("conflict");
                 }
-                (' ', '?') => {
+                ('?', '?') => {
                     untracked += 1;
                     formatted_entries.push("untracked");
                 }
@@ -357,6 +327,36 @@ impl GitStatusPlugin {
             None
         }
     }
+
+    fn decorate(&mut self, mut entry: DecoratedEntry) -> DecoratedEntry {
+        let spinner = SPINNER.write();
+        spinner.set_status("Checking Git status...".to_string());
+
+        if let Some((statuses, branch, commit)) = self.get_git_info(&entry.path) {
+            let (status_summary, staged, modified, untracked, conflicts) =
+                Self::format_git_status(&statuses);
+            entry
+                .custom_fields
+                .insert("git_status".to_string(), status_summary);
+            entry.custom_fields.insert("git_branch".to_string(), branch);
+            entry.custom_fields.insert("git_commit".to_string(), commit);
+            entry
+                .custom_fields
+                .insert("git_staged".to_string(), staged.to_string());
+            entry
+                .custom_fields
+                .insert("git_modified".to_string(), modified.to_string());
+            entry
+                .custom_fields
+                .insert("git_untracked".to_string(), untracked.to_string());
+            entry
+                .custom_fields
+                .insert("git_conflicts".to_string(), conflicts.to_string());
+        }
+
+        spinner.finish();
+        entry
+    }
 }
 
 impl Plugin for GitStatusPlugin {
@@ -364,6 +364,9 @@ impl Plugin for GitStatusPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -377,35 +380,15 @@ impl Plugin for GitStatusPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        let spinner = SPINNER.write();
-                        spinner.set_status("Checking Git status...".to_string());
-
-                        if let Some((status, branch, commit)) = Self::get_git_info(&entry.path) {
-                            let (status_summary, staged, modified, untracked, conflicts) =
-                                Self::format_git_status(&status);
-                            entry
-                                .custom_fields
-                                .insert("git_status".to_string(), status_summary);
-                            entry.custom_fields.insert("git_branch".to_string(), branch);
-                            entry.custom_fields.insert("git_commit".to_string(), commit);
-                            entry
-                                .custom_fields
-                                .insert("git_staged".to_string(), staged.to_string());
-                            entry
-                                .custom_fields
-                                .insert("git_modified".to_string(), modified.to_string());
-                            entry
-                                .custom_fields
-                                .insert("git_untracked".to_string(), untracked.to_string());
-                            entry
-                                .custom_fields
-                                .insert("git_conflicts".to_string(), conflicts.to_string());
-                        }
-
-                        spinner.finish();
-                        PluginResponse::Decorated(entry)
+                    PluginRequest::Decorate(entry) => {
+                        PluginResponse::Decorated(self.decorate(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries
+                            .into_iter()
+                            .map(|entry| self.decorate(entry))
+                            .collect(),
+                    ),
                     PluginRequest::FormatField(entry, format) => {
                         let field = self.format_git_info(&entry, &format);
                         PluginResponse::FormattedField(field)
@@ -414,6 +397,7 @@ impl Plugin for GitStatusPlugin {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
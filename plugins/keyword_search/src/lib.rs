@@ -3,7 +3,9 @@ use colored::Colorize;
 use dialoguer::{MultiSelect, Select};
 use itertools::Itertools;
 use lazy_static::lazy_static;
-use lla_plugin_interface::{Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::components::{BoxComponent, BoxStyle, HelpFormatter, LlaDialoguerTheme},
@@ -544,6 +546,21 @@ impl KeywordSearchPlugin {
 
         Ok(())
     }
+
+    fn decorate(&mut self, mut entry: DecoratedEntry) -> DecoratedEntry {
+        if let Some(matches) = entry
+            .path
+            .is_file()
+            .then(|| self.search_file(&entry.path))
+            .flatten()
+        {
+            entry.custom_fields.insert(
+                "keyword_matches".to_string(),
+                toml::to_string(&matches).unwrap_or_default(),
+            );
+        }
+        entry
+    }
 }
 
 impl Plugin for KeywordSearchPlugin {
@@ -551,6 +568,9 @@ impl Plugin for KeywordSearchPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -564,20 +584,15 @@ impl Plugin for KeywordSearchPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        if let Some(matches) = entry
-                            .path
-                            .is_file()
-                            .then(|| self.search_file(&entry.path))
-                            .flatten()
-                        {
-                            entry.custom_fields.insert(
-                                "keyword_matches".to_string(),
-                                toml::to_string(&matches).unwrap_or_default(),
-                            );
-                        }
-                        PluginResponse::Decorated(entry)
+                    PluginRequest::Decorate(entry) => {
+                        PluginResponse::Decorated(self.decorate(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries
+                            .into_iter()
+                            .map(|entry| self.decorate(entry))
+                            .collect(),
+                    ),
                     PluginRequest::FormatField(entry, _format) => {
                         let field = entry
                             .custom_fields
@@ -722,6 +737,7 @@ impl Plugin for KeywordSearchPlugin {
                         };
                         response
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
@@ -1,14 +1,16 @@
 use lazy_static::lazy_static;
-use lla_plugin_interface::{Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
+    git::GitRepoCache,
     ui::components::{BoxComponent, BoxStyle, HelpFormatter, KeyValue, List, Spinner},
     ActionRegistry, BasePlugin, ConfigurablePlugin, ProtobufHandler,
 };
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use serde_json;
-use std::{collections::HashMap, path::Path, process::Command};
+use std::{collections::HashMap, path::Path};
 
 lazy_static! {
     static ref SPINNER: RwLock<Spinner> = RwLock::new(Spinner::new());
@@ -90,6 +92,7 @@ impl PluginConfig for CommitConfig {}
 
 pub struct LastGitCommitPlugin {
     base: BasePlugin<CommitConfig>,
+    repos: GitRepoCache,
 }
 
 impl LastGitCommitPlugin {
@@ -97,6 +100,7 @@ impl LastGitCommitPlugin {
         let plugin_name = env!("CARGO_PKG_NAME");
         let plugin = Self {
             base: BasePlugin::with_name(plugin_name),
+            repos: GitRepoCache::new(),
         };
         if let Err(e) = plugin.base.save_config() {
             eprintln!("[LastGitCommitPlugin] Failed to save config: {}", e);
@@ -104,46 +108,10 @@ impl LastGitCommitPlugin {
         plugin
     }
 
-    fn get_last_commit_info(path: &Path) -> Option<(String, String, String)> {
-        let output = Command::new("git")
-            .args([
-                "log",
-                "-1",
-                "--format=format:{ \"hash\": \"%h\", \"author\": \"%an\", \"time\": \"%ar\" }",
-                "--",
-                path.to_str()?,
-            ])
-            .output()
-            .ok()?;
-
-        let output_str = String::from_utf8(output.stdout).ok()?;
-        let trimmed = output_str.trim();
-
-        if trimmed.is_empty() {
-            return None;
-        }
-
-        match serde_json::from_str::<serde_json::Value>(trimmed) {
-            Ok(json) => {
-                let hash = match json.get("hash").and_then(|v| v.as_str()) {
-                    Some(h) => h.to_string(),
-                    None => return None,
-                };
-
-                let author = match json.get("author").and_then(|v| v.as_str()) {
-                    Some(a) => a.to_string(),
-                    None => return None,
-                };
-
-                let time = match json.get("time").and_then(|v| v.as_str()) {
-                    Some(t) => t.to_string(),
-                    None => return None,
-                };
-
-                Some((hash, author, time))
-            }
-            Err(_) => None,
-        }
+    fn get_last_commit_info(&mut self, path: &Path) -> Option<(String, String, String)> {
+        let commit = self.repos.last_commit(path)?;
+        let time = commit.relative_time();
+        Some((commit.short_id, commit.author, time))
     }
 
     fn format_commit_info(
@@ -218,6 +186,24 @@ impl LastGitCommitPlugin {
             None
         }
     }
+
+    fn decorate(&mut self, mut entry: DecoratedEntry) -> DecoratedEntry {
+        let spinner = SPINNER.write();
+        spinner.set_status("Checking last commit...".to_string());
+
+        if let Some((commit_hash, author, time)) = self.get_last_commit_info(&entry.path) {
+            entry
+                .custom_fields
+                .insert("commit_hash".to_string(), commit_hash);
+            entry
+                .custom_fields
+                .insert("commit_author".to_string(), author);
+            entry.custom_fields.insert("commit_time".to_string(), time);
+        }
+
+        spinner.finish();
+        entry
+    }
 }
 
 impl Plugin for LastGitCommitPlugin {
@@ -225,6 +211,9 @@ impl Plugin for LastGitCommitPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[capability::DECORATE_BATCH]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -238,25 +227,15 @@ impl Plugin for LastGitCommitPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        let spinner = SPINNER.write();
-                        spinner.set_status("Checking last commit...".to_string());
-
-                        if let Some((commit_hash, author, time)) =
-                            Self::get_last_commit_info(&entry.path)
-                        {
-                            entry
-                                .custom_fields
-                                .insert("commit_hash".to_string(), commit_hash);
-                            entry
-                                .custom_fields
-                                .insert("commit_author".to_string(), author);
-                            entry.custom_fields.insert("commit_time".to_string(), time);
-                        }
-
-                        spinner.finish();
-                        PluginResponse::Decorated(entry)
+                    PluginRequest::Decorate(entry) => {
+                        PluginResponse::Decorated(self.decorate(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries
+                            .into_iter()
+                            .map(|entry| self.decorate(entry))
+                            .collect(),
+                    ),
                     PluginRequest::FormatField(entry, format) => {
                         let field = self.format_commit_info(&entry, &format);
                         PluginResponse::FormattedField(field)
@@ -265,6 +244,7 @@ impl Plugin for LastGitCommitPlugin {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }
@@ -1,6 +1,8 @@
 use colored::Colorize;
 use lazy_static::lazy_static;
-use lla_plugin_interface::{Plugin, PluginRequest, PluginResponse};
+use lla_plugin_interface::{
+    capability, DecoratedEntry, Handshake, Plugin, PluginRequest, PluginResponse,
+};
 use lla_plugin_utils::{
     config::PluginConfig,
     ui::components::{BoxComponent, BoxStyle, HelpFormatter, Spinner},
@@ -47,6 +49,12 @@ lazy_static! {
                         vec![],
                     );
 
+                help.add_section("Formatters".to_string()).add_command(
+                    "chart".to_string(),
+                    "Chart the files of a listing by size, largest first".to_string(),
+                    vec!["lla --format sizeviz:chart".to_string()],
+                );
+
                 println!(
                     "{}",
                     BoxComponent::new(help.render(&SizeConfig::default().colors))
@@ -263,6 +271,76 @@ impl FileSizeVisualizerPlugin {
             })
             .flatten()
     }
+
+    fn format_chart(&self, entries: &[DecoratedEntry]) -> String {
+        let mut files: Vec<&DecoratedEntry> =
+            entries.iter().filter(|e| e.metadata.is_file).collect();
+        files.sort_by(|a, b| b.metadata.size.cmp(&a.metadata.size));
+
+        let total_size: u64 = files.iter().map(|e| e.metadata.size).sum();
+        let max_size = files.first().map_or(0, |e| e.metadata.size).max(1);
+        let names: Vec<String> = files
+            .iter()
+            .map(|e| {
+                e.path
+                    .file_name()
+                    .unwrap_or(e.path.as_os_str())
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        let name_width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+
+        let mut lines: Vec<String> = files
+            .iter()
+            .zip(&names)
+            .map(|(entry, name)| {
+                let size = entry.metadata.size;
+                let bar = Self::size_to_bar(size, max_size, 30);
+                format!(
+                    "{:<width$}  {}  {:>9}  {:>5}%",
+                    name,
+                    bar.color(self.size_color(size)),
+                    Self::format_size(size).bright_yellow(),
+                    format!("{:.1}", Self::get_percentage(size, total_size)).bright_magenta(),
+                    width = name_width
+                )
+            })
+            .collect();
+        lines.push(format!(
+            "{} {} in {} files",
+            "Total".bright_blue(),
+            Self::format_size(total_size).bright_yellow(),
+            files.len()
+        ));
+        lines.join("\n")
+    }
+
+    fn size_color(&self, size: u64) -> colored::Color {
+        match self.get_size_color(size).as_str() {
+            "bright_green" => colored::Color::BrightGreen,
+            "bright_cyan" => colored::Color::BrightCyan,
+            "bright_yellow" => colored::Color::BrightYellow,
+            "bright_red" => colored::Color::BrightRed,
+            "bright_magenta" => colored::Color::BrightMagenta,
+            _ => colored::Color::White,
+        }
+    }
+
+    fn decorate(&mut self, mut entry: DecoratedEntry) -> DecoratedEntry {
+        let spinner = SPINNER.write();
+        spinner.set_status("Calculating size...".to_string());
+
+        if entry.path.is_file() {
+            let size = entry.metadata.size;
+            entry
+                .custom_fields
+                .insert("size".to_string(), size.to_string());
+        }
+
+        spinner.finish();
+        entry
+    }
 }
 
 impl Plugin for FileSizeVisualizerPlugin {
@@ -270,6 +348,12 @@ impl Plugin for FileSizeVisualizerPlugin {
         match self.decode_request(request) {
             Ok(request) => {
                 let response = match request {
+                    PluginRequest::Handshake(_) => {
+                        PluginResponse::Handshake(Handshake::current(&[
+                            capability::DECORATE_BATCH,
+                            capability::FORMATTERS,
+                        ]))
+                    }
                     PluginRequest::GetName => {
                         PluginResponse::Name(env!("CARGO_PKG_NAME").to_string())
                     }
@@ -283,28 +367,31 @@ impl Plugin for FileSizeVisualizerPlugin {
                         "default".to_string(),
                         "long".to_string(),
                     ]),
-                    PluginRequest::Decorate(mut entry) => {
-                        let spinner = SPINNER.write();
-                        spinner.set_status("Calculating size...".to_string());
-
-                        if entry.path.is_file() {
-                            let size = entry.metadata.size;
-                            entry
-                                .custom_fields
-                                .insert("size".to_string(), size.to_string());
-                        }
-
-                        spinner.finish();
-                        PluginResponse::Decorated(entry)
+                    PluginRequest::Decorate(entry) => {
+                        PluginResponse::Decorated(self.decorate(entry))
                     }
+                    PluginRequest::DecorateBatch(entries) => PluginResponse::DecoratedBatch(
+                        entries
+                            .into_iter()
+                            .map(|entry| self.decorate(entry))
+                            .collect(),
+                    ),
                     PluginRequest::FormatField(entry, format) => {
                         let field = self.format_size_info(&entry, &format);
                         PluginResponse::FormattedField(field)
                     }
+                    PluginRequest::GetFormatters => {
+                        PluginResponse::Formatters(vec!["chart".to_string()])
+                    }
+                    PluginRequest::FormatEntries(formatter, entries) => match formatter.as_str() {
+                        "chart" => PluginResponse::FormattedEntries(self.format_chart(&entries)),
+                        _ => PluginResponse::Error(format!("Unknown formatter: {}", formatter)),
+                    },
                     PluginRequest::PerformAction(action, args) => {
                         let result = ACTION_REGISTRY.read().handle(&action, &args);
                         PluginResponse::ActionResult(result)
                     }
+                    request => self.unsupported_request(&request),
                 };
                 self.encode_response(response)
             }